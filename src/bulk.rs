@@ -0,0 +1,109 @@
+use std::fmt;
+use std::sync::Arc;
+
+use crate::error::PubmedError;
+use crate::types::PubmedArticle;
+
+/// The order in which [`crate::Client::articles_stream`] yields chunks.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ResultOrder {
+    /// As soon as each chunk is done
+    #[default]
+    Completion,
+    /// In the order of the input IDs, holding back chunks that finish early
+    Input,
+}
+
+/// Progress of a bulk fetch, reported after each chunk.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BulkProgress {
+    pub chunks_done: usize,
+    pub chunks_total: usize,
+    pub failed_chunks: usize,
+    pub articles_done: usize,
+}
+
+pub type ProgressCallback = Arc<dyn Fn(&BulkProgress) + Send + Sync>;
+
+/// Settings for [`crate::Client::articles_stream`]. Requests still pass
+/// through the client's rate limiter, so `concurrency` only bounds how many
+/// are in flight at once.
+#[derive(Clone)]
+pub struct BulkFetchOptions {
+    /// IDs per EFetch request
+    pub chunk_size: usize,
+    /// Maximum number of requests in flight
+    pub concurrency: usize,
+    pub order: ResultOrder,
+    pub progress: Option<ProgressCallback>,
+}
+
+impl BulkFetchOptions {
+    #[must_use]
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    #[must_use]
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency;
+        self
+    }
+
+    #[must_use]
+    pub fn with_order(mut self, order: ResultOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Calls `progress` after each chunk, whether it succeeded or not.
+    #[must_use]
+    pub fn with_progress(
+        mut self,
+        progress: impl Fn(&BulkProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some(Arc::new(progress));
+        self
+    }
+}
+
+impl Default for BulkFetchOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: crate::client::EFETCH_POST_THRESHOLD,
+            concurrency: 4,
+            order: ResultOrder::default(),
+            progress: None,
+        }
+    }
+}
+
+impl fmt::Debug for BulkFetchOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BulkFetchOptions")
+            .field("chunk_size", &self.chunk_size)
+            .field("concurrency", &self.concurrency)
+            .field("order", &self.order)
+            .field("progress", &self.progress.is_some())
+            .finish()
+    }
+}
+
+/// The outcome of fetching one chunk of IDs. A failed chunk does not stop
+/// the others; its IDs can be retried later.
+#[derive(Debug)]
+pub struct ChunkResult {
+    /// Position of the chunk in the input
+    pub index: usize,
+    pub ids: Vec<u64>,
+    pub result: Result<Vec<PubmedArticle>, PubmedError>,
+}
+
+/// Everything a bulk fetch returned, see [`crate::Client::bulk_articles`].
+#[derive(Debug, Default)]
+pub struct BulkFetchReport {
+    pub articles: Vec<PubmedArticle>,
+    /// The IDs of each failed chunk, with the error
+    pub failures: Vec<(Vec<u64>, PubmedError)>,
+}
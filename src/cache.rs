@@ -0,0 +1,290 @@
+use roxmltree::ParsingOptions;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime};
+
+/// An on-disk cache of raw EFetch XML, one `<PubmedArticle>` per file,
+/// used by [`crate::Client::articles`] when set via
+/// [`crate::Client::with_cache`].
+///
+/// Records are stored by PMID; a record is only replaced by one with the
+/// same or a later `DateRevised`. Expired records (see
+/// [`ArticleCache::with_ttl`]) are fetched again. In offline mode, PMIDs
+/// missing from the cache are skipped instead of fetched, and expired
+/// records are still served.
+///
+/// All methods do blocking file I/O; [`crate::Client`] calls them through
+/// `tokio::task::spawn_blocking`.
+#[derive(Debug, Clone)]
+pub struct ArticleCache {
+    dir: PathBuf,
+    ttl: Option<Duration>,
+    max_size: Option<u64>,
+    offline: bool,
+    /// Total size of the cached records, counted on the first store with a
+    /// size cap and kept up to date from then on; shared between clones
+    size: Arc<Mutex<Option<u64>>>,
+}
+
+impl ArticleCache {
+    /// A cache in `dir`, which is created on first write.
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl: None,
+            max_size: None,
+            offline: false,
+            size: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Treats records older than `ttl` as missing.
+    #[must_use]
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// Caps the total size of cached records at `bytes`. When the cap is
+    /// exceeded, the least recently written records are evicted until the
+    /// cache is down to 90% of it.
+    #[must_use]
+    pub fn with_max_size(mut self, bytes: u64) -> Self {
+        self.max_size = Some(bytes);
+        self
+    }
+
+    /// Serves only from the cache, never from the network. Records past
+    /// their TTL are served too, as there is nothing to replace them with.
+    #[must_use]
+    pub fn with_offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    #[must_use]
+    pub fn is_offline(&self) -> bool {
+        self.offline
+    }
+
+    /// Returns the cached `<PubmedArticle>` XML for `pmid`, unless it is
+    /// missing or, when online, expired.
+    #[must_use]
+    pub fn get(&self, pmid: u64) -> Option<String> {
+        let path = self.path(pmid);
+        let modified = fs::metadata(&path).ok()?.modified().ok()?;
+        if let Some(ttl) = self.ttl.filter(|_| !self.offline) {
+            let age = SystemTime::now()
+                .duration_since(modified)
+                .unwrap_or_default();
+            if age > ttl {
+                return None;
+            }
+        }
+        fs::read_to_string(path).ok()
+    }
+
+    /// Stores every `<PubmedArticle>` of an EFetch response and returns how
+    /// many were written.
+    pub fn store(&self, xml: &str) -> io::Result<usize> {
+        let doc = roxmltree::Document::parse_with_options(xml, Self::parsing_options())
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        let mut stored = 0;
+        let mut added: i64 = 0;
+        for node in doc
+            .root()
+            .descendants()
+            .filter(|n| n.is_element() && n.tag_name().name() == "PubmedArticle")
+        {
+            let Some(pmid) = Self::pmid(&node) else {
+                continue;
+            };
+            let path = self.path(pmid);
+            let revised = Self::date_revised(&node);
+            let cached_revised = fs::read_to_string(&path).ok().and_then(|cached| {
+                let doc = roxmltree::Document::parse_with_options(&cached, Self::parsing_options())
+                    .ok()?;
+                Self::date_revised(&doc.root_element())
+            });
+            if cached_revised.is_some() && cached_revised > revised {
+                continue; // Keep the newer revision
+            }
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            let replaced = fs::metadata(&path).map_or(0, |m| m.len());
+            let record = &xml[node.range()];
+            let tmp = Self::temp_path(&path);
+            fs::write(&tmp, record)?;
+            if let Err(e) = fs::rename(&tmp, &path) {
+                let _ = fs::remove_file(&tmp);
+                return Err(e);
+            }
+            stored += 1;
+            added += Self::signed(record.len() as u64) - Self::signed(replaced);
+        }
+        if stored > 0 {
+            self.enforce_max_size(added)?;
+        }
+        Ok(stored)
+    }
+
+    pub fn remove(&self, pmid: u64) -> io::Result<()> {
+        let path = self.path(pmid);
+        let removed = fs::metadata(&path).map_or(0, |m| m.len());
+        match fs::remove_file(path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => {
+                self.adjust_size(-Self::signed(removed));
+                Ok(())
+            }
+        }
+    }
+
+    /// Removes all cached records.
+    pub fn clear(&self) -> io::Result<()> {
+        for (path, _, _) in self.entries()? {
+            fs::remove_file(path)?;
+        }
+        *self.lock_size() = Some(0);
+        Ok(())
+    }
+
+    // Unique per process and call, so concurrent stores of the same record
+    // do not share a temporary file
+    fn temp_path(path: &Path) -> PathBuf {
+        static COUNTER: AtomicU64 = AtomicU64::new(0);
+        let n = COUNTER.fetch_add(1, Ordering::Relaxed);
+        path.with_extension(format!("tmp{}_{n}", std::process::id()))
+    }
+
+    // Spreads records over 1000 subdirectories to keep directories small
+    fn path(&self, pmid: u64) -> PathBuf {
+        self.dir
+            .join(format!("{:03}", pmid % 1000))
+            .join(format!("{pmid}.xml"))
+    }
+
+    fn parsing_options<'a>() -> ParsingOptions<'a> {
+        ParsingOptions {
+            allow_dtd: true,
+            nodes_limit: u32::MAX,
+            ..Default::default()
+        }
+    }
+
+    fn pmid(node: &roxmltree::Node) -> Option<u64> {
+        node.children()
+            .find(|n| n.has_tag_name("MedlineCitation"))?
+            .children()
+            .find(|n| n.has_tag_name("PMID"))?
+            .text()?
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    /// `DateRevised` as a sortable `YYYYMMDD` string.
+    fn date_revised(node: &roxmltree::Node) -> Option<String> {
+        let date = node
+            .children()
+            .find(|n| n.has_tag_name("MedlineCitation"))?
+            .children()
+            .find(|n| n.has_tag_name("DateRevised"))?;
+        let part = |name: &str| -> Option<u32> {
+            date.children()
+                .find(|n| n.has_tag_name(name))?
+                .text()?
+                .trim()
+                .parse()
+                .ok()
+        };
+        Some(format!(
+            "{:04}{:02}{:02}",
+            part("Year")?,
+            part("Month").unwrap_or(0),
+            part("Day").unwrap_or(0)
+        ))
+    }
+
+    // All cached records as (path, size, modification time)
+    fn entries(&self) -> io::Result<Vec<(PathBuf, u64, SystemTime)>> {
+        let mut ret = vec![];
+        let shards = match fs::read_dir(&self.dir) {
+            Ok(shards) => shards,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(ret),
+            Err(e) => return Err(e),
+        };
+        for shard in shards {
+            let shard = shard?;
+            if !shard.file_type()?.is_dir() {
+                continue;
+            }
+            for entry in fs::read_dir(shard.path())? {
+                let entry = entry?;
+                let path = entry.path();
+                if path.extension().and_then(|e| e.to_str()) != Some("xml") {
+                    continue;
+                }
+                let metadata = entry.metadata()?;
+                ret.push((path, metadata.len(), metadata.modified()?));
+            }
+        }
+        Ok(ret)
+    }
+
+    fn lock_size(&self) -> std::sync::MutexGuard<'_, Option<u64>> {
+        self.size
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    fn signed(n: u64) -> i64 {
+        i64::try_from(n).unwrap_or(i64::MAX)
+    }
+
+    fn adjust_size(&self, delta: i64) {
+        if let Some(size) = self.lock_size().as_mut() {
+            *size = size.saturating_add_signed(delta);
+        }
+    }
+
+    // Scans the cache directory only to count its size once, and when the
+    // cap is exceeded; eviction goes below the cap so that this is rare
+    fn enforce_max_size(&self, added: i64) -> io::Result<()> {
+        let Some(max_size) = self.max_size else {
+            return Ok(());
+        };
+        let mut size = self.lock_size();
+        let total = match *size {
+            Some(total) => total.saturating_add_signed(added),
+            None => self.entries()?.iter().map(|(_, size, _)| size).sum(),
+        };
+        *size = Some(total);
+        if total <= max_size {
+            return Ok(());
+        }
+        let target = max_size / 10 * 9;
+        let mut entries = self.entries()?;
+        let mut total: u64 = entries.iter().map(|(_, size, _)| size).sum();
+        entries.sort_by_key(|(_, _, modified)| *modified);
+        for (path, entry_size, _) in entries {
+            if total <= target {
+                break;
+            }
+            fs::remove_file(path)?;
+            total -= entry_size;
+        }
+        *size = Some(total);
+        Ok(())
+    }
+}
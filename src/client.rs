@@ -1,88 +1,754 @@
+use futures::stream::{self, BoxStream, StreamExt};
 use roxmltree::ParsingOptions;
 use serde::{Deserialize, Serialize};
-use std::error::Error;
-use std::fs;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
 
-use crate::types::PubmedArticle;
+use crate::bulk::{BulkFetchOptions, BulkFetchReport, BulkProgress, ChunkResult, ResultOrder};
+use crate::cache::ArticleCache;
+use crate::config::{ApiKeySource, EnvironmentConfig};
+use crate::error::PubmedError;
+use crate::query::SearchOptions;
+use crate::rate_limiter::{RateLimiter, API_KEY_REQUESTS_PER_SECOND, DEFAULT_REQUESTS_PER_SECOND};
+use crate::retry::{Attempt, RetryPolicy};
+use crate::transport::{HttpRequest, ReqwestTransport, Transport};
+use crate::types::{
+    CitationKey, CitationMatch, CitationMatchStatus, ConverterIdType, DatabaseInfo,
+    DocumentSummary, HistoryHandle, IdRecord, LinkName, LinkSet, PubmedArticle, SearchResult,
+    SpellingSuggestion,
+};
+
+const EUTILS_BASE_URL: &str = "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/";
+
+/// ESearch refuses to page past this many IDs for a single query.
+pub const MAX_ESEARCH_RESULTS: u64 = 10_000;
+
+/// `Client::articles` switches from GET to POST above this many IDs.
+pub const EFETCH_POST_THRESHOLD: usize = 200;
+
+/// Maximum number of IDs `Client::articles` sends in a single EFetch request.
+pub const EFETCH_BATCH_SIZE: usize = 1_000;
+
+/// Maximum number of citations `Client::citation_match` sends per request.
+pub const ECITMATCH_BATCH_SIZE: usize = 50;
+
+/// Maximum number of IDs the PMC ID Converter accepts per request.
+pub const IDCONV_BATCH_SIZE: usize = 200;
+
+const IDCONV_URL: &str = "https://www.ncbi.nlm.nih.gov/pmc/utils/idconv/v1.0/";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Client {
     api_key: Option<String>,
+    #[serde(skip)]
+    api_key_source: ApiKeySource,
+    #[serde(default = "default_base_url")]
+    base_url: String,
+    #[serde(default = "default_id_converter_url")]
+    id_converter_url: String,
+    #[serde(default)]
+    tool: Option<String>,
+    #[serde(default)]
+    email: Option<String>,
+    #[serde(skip, default = "default_transport")]
+    transport: Arc<dyn Transport>,
+    #[serde(skip)]
+    rate_limiter: RateLimiter,
+    #[serde(skip)]
+    retry_policy: RetryPolicy,
+    #[serde(skip)]
+    cache: Option<ArticleCache>,
 }
 
 impl Client {
-    /// Creates a new `Client`, optionally loading an API key from a file
-    /// named `ncbi_key` in the current working directory. Whitespace is
-    /// trimmed from the key.
+    /// Creates a new `Client`, taking the API key, `tool` and `email` from
+    /// the environment as described in [`EnvironmentConfig`]. Use
+    /// [`Client::api_key_source`] to check where the key came from.
     #[must_use]
     pub fn new() -> Self {
-        let api_key = fs::read_to_string("ncbi_key")
-            .ok()
-            .map(|k| k.trim().to_string())
-            .filter(|k| !k.is_empty());
-        Client { api_key }
+        let config = EnvironmentConfig::load();
+        let mut client = Self::new_with_optional_key(config.api_key);
+        client.api_key_source = config.api_key_source;
+        client.tool = config.tool;
+        client.email = config.email;
+        client
     }
 
     /// Creates a new `Client` with an explicit API key.
     pub fn with_api_key(api_key: impl Into<String>) -> Self {
         let key = api_key.into();
+        let mut client = Self::new_with_optional_key(if key.is_empty() { None } else { Some(key) });
+        if client.api_key.is_some() {
+            client.api_key_source = ApiKeySource::Explicit;
+        }
+        client
+    }
+
+    /// Returns a [`ClientBuilder`] for configuring the base URL, `tool` and
+    /// `email` parameters, timeouts and the underlying HTTP client.
+    #[must_use]
+    pub fn builder() -> ClientBuilder {
+        ClientBuilder::default()
+    }
+
+    fn new_with_optional_key(api_key: Option<String>) -> Self {
+        let requests_per_second = if api_key.is_some() {
+            API_KEY_REQUESTS_PER_SECOND
+        } else {
+            DEFAULT_REQUESTS_PER_SECOND
+        };
         Client {
-            api_key: if key.is_empty() { None } else { Some(key) },
+            api_key,
+            api_key_source: ApiKeySource::Unset,
+            base_url: default_base_url(),
+            id_converter_url: default_id_converter_url(),
+            tool: None,
+            email: None,
+            transport: default_transport(),
+            rate_limiter: RateLimiter::new(requests_per_second),
+            retry_policy: RetryPolicy::default(),
+            cache: None,
         }
     }
 
-    fn api_key_param(&self) -> String {
-        match &self.api_key {
-            Some(key) => format!("&api_key={key}"),
-            None => String::new(),
+    /// Replaces the default budget of 3 (or, with an API key, 10) requests
+    /// per second, e.g. for a negotiated higher quota. The limit is shared
+    /// by all clones made afterwards.
+    #[must_use]
+    pub fn with_rate_limit(mut self, requests_per_second: u32) -> Self {
+        self.rate_limiter = RateLimiter::new(requests_per_second);
+        self
+    }
+
+    /// Replaces the default [`RetryPolicy`]; use [`RetryPolicy::none`] to
+    /// disable retries.
+    #[must_use]
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
+    /// Sends all requests through `transport`, e.g. a [`ReplayTransport`]
+    /// serving recorded fixtures in tests.
+    ///
+    /// [`ReplayTransport`]: crate::transport::ReplayTransport
+    #[must_use]
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    /// Caches EFetch records on disk, see [`ArticleCache`].
+    #[must_use]
+    pub fn with_cache(mut self, cache: ArticleCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Where the API key came from, to diagnose a missing or unexpected key.
+    #[must_use]
+    pub fn api_key_source(&self) -> &ApiKeySource {
+        &self.api_key_source
+    }
+
+    #[must_use]
+    pub fn rate_limiter(&self) -> &RateLimiter {
+        &self.rate_limiter
+    }
+
+    /// Builds an E-utilities URL for `endpoint` (e.g. `esearch.fcgi`),
+    /// percent-encoding all parameters and adding `tool`, `email` and the
+    /// API key if set.
+    pub(crate) fn eutils_url(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+    ) -> Result<reqwest::Url, PubmedError> {
+        let mut url = reqwest::Url::parse(&self.base_url)
+            .and_then(|base| base.join(endpoint))
+            .map_err(|e| PubmedError::InvalidRequest(e.to_string()))?;
+        {
+            let mut pairs = url.query_pairs_mut();
+            pairs.extend_pairs(params);
+            if let Some(tool) = &self.tool {
+                pairs.append_pair("tool", tool);
+            }
+            if let Some(email) = &self.email {
+                pairs.append_pair("email", email);
+            }
+            if let Some(key) = &self.api_key {
+                pairs.append_pair("api_key", key);
+            }
         }
+        Ok(url)
     }
 
     pub async fn article_ids_from_query(
         &self,
         query: &str,
         max: u64,
-    ) -> Result<Vec<u64>, Box<dyn Error>> {
-        let url = format!(
-            "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/esearch.fcgi?db=pubmed&retmode=json&retmax={}&term={}{}",
-            max, query, self.api_key_param()
-        );
-        let json: serde_json::Value = reqwest::get(&url).await?.json().await?;
-        match json["esearchresult"]["idlist"].as_array() {
-            Some(idlist) => Ok(idlist
-                .iter()
-                .filter_map(|id| {
-                    id.as_str().and_then(|x| {
-                        if let Ok(u) = x.parse::<u64>() {
-                            Some(u)
-                        } else {
-                            eprintln!(
-                                "PubMed::article_ids_from_query: '{x}' should be a numeric ID"
-                            );
-                            None
-                        }
-                    })
+    ) -> Result<Vec<u64>, PubmedError> {
+        Ok(self.search(query, 0, max).await?.ids)
+    }
+
+    /// Runs an ESearch query and returns one page of results, starting at
+    /// `retstart` and containing at most `retmax` IDs, together with the
+    /// total hit count, query translation and any warnings.
+    pub async fn search(
+        &self,
+        query: &str,
+        retstart: u64,
+        retmax: u64,
+    ) -> Result<SearchResult, PubmedError> {
+        self.search_with_options(query, retstart, retmax, &SearchOptions::default())
+            .await
+    }
+
+    /// Like [`Client::search`], but if `query` finds nothing and ESpell
+    /// suggests a different spelling, searches again with the suggestion.
+    /// The returned result's `corrected_query` is set in that case.
+    pub async fn search_with_spelling_fallback(
+        &self,
+        query: &str,
+        retstart: u64,
+        retmax: u64,
+    ) -> Result<SearchResult, PubmedError> {
+        let options = SearchOptions {
+            spelling_fallback: true,
+            ..Default::default()
+        };
+        self.search_with_options(query, retstart, retmax, &options)
+            .await
+    }
+
+    /// Like [`Client::search`], with sort order, date filters and spelling
+    /// fallback taken from `options`.
+    pub async fn search_with_options(
+        &self,
+        query: &str,
+        retstart: u64,
+        retmax: u64,
+        options: &SearchOptions,
+    ) -> Result<SearchResult, PubmedError> {
+        let result = self.esearch(query, retstart, retmax, options).await?;
+        if result.count > 0 || !options.spelling_fallback {
+            return Ok(result);
+        }
+        let suggestion = self.espell(query).await?;
+        match suggestion.corrected_query {
+            Some(corrected) if suggestion.has_correction() => {
+                let mut result = self.esearch(&corrected, retstart, retmax, options).await?;
+                result.corrected_query = Some(corrected);
+                Ok(result)
+            }
+            _ => Ok(result),
+        }
+    }
+
+    async fn esearch(
+        &self,
+        query: &str,
+        retstart: u64,
+        retmax: u64,
+        options: &SearchOptions,
+    ) -> Result<SearchResult, PubmedError> {
+        let retstart = retstart.to_string();
+        let retmax = retmax.to_string();
+        let option_params = options.to_params();
+        let mut params = vec![
+            ("db", "pubmed"),
+            ("retmode", "json"),
+            ("retstart", retstart.as_str()),
+            ("retmax", retmax.as_str()),
+            ("term", query),
+        ];
+        params.extend(option_params.iter().map(|(k, v)| (*k, v.as_str())));
+        let url = self.eutils_url("esearch.fcgi", &params)?;
+        let json = self.get_json(url).await?;
+        SearchResult::new_from_json(&json).ok_or_else(|| PubmedError::unexpected("ESearch"))
+    }
+
+    /// Asks ESpell for spelling corrections of `query`.
+    pub async fn espell(&self, query: &str) -> Result<SpellingSuggestion, PubmedError> {
+        let url = self.eutils_url("espell.fcgi", &[("db", "pubmed"), ("term", query)])?;
+        let text = self.send(HttpRequest::get(url)).await?;
+        let doc = roxmltree::Document::parse(&text)?;
+        Ok(SpellingSuggestion::new_from_xml(&doc.root_element()))
+    }
+
+    /// Returns a pager that walks all results of `query`, `page_size` IDs at
+    /// a time. Note that ESearch only serves the first
+    /// [`MAX_ESEARCH_RESULTS`] IDs of any query.
+    #[must_use]
+    pub fn search_pages(&self, query: &str, page_size: u64) -> SearchPager<'_> {
+        self.search_pages_with_options(query, page_size, SearchOptions::default())
+    }
+
+    /// Like [`Client::search_pages`], with every page requested using
+    /// `options`.
+    #[must_use]
+    pub fn search_pages_with_options(
+        &self,
+        query: &str,
+        page_size: u64,
+        options: SearchOptions,
+    ) -> SearchPager<'_> {
+        SearchPager {
+            client: self,
+            query: query.to_string(),
+            options,
+            page_size: page_size.max(1),
+            retstart: 0,
+            count: None,
+        }
+    }
+
+    /// Fetches the articles for `ids`, returned in the same order as the
+    /// input. Long ID lists are split into batches of [`EFETCH_BATCH_SIZE`];
+    /// batches above [`EFETCH_POST_THRESHOLD`] IDs are sent via HTTP POST to
+    /// stay clear of URL length limits. IDs that PubMed does not return are
+    /// skipped, and duplicate IDs yield a single article.
+    ///
+    /// With an [`ArticleCache`], cached records are used where available and
+    /// fetched records are added to the cache.
+    pub async fn articles(&self, ids: &[u64]) -> Result<Vec<PubmedArticle>, PubmedError> {
+        let mut seen = HashSet::new();
+        let ids: Vec<u64> = ids.iter().copied().filter(|id| seen.insert(*id)).collect();
+        let mut articles = vec![];
+        let mut missing = vec![];
+        match &self.cache {
+            Some(cache) => {
+                let lookup = cache.clone();
+                let lookup_ids = ids.clone();
+                let cached: Vec<Option<String>> = tokio::task::spawn_blocking(move || {
+                    lookup_ids.iter().map(|id| lookup.get(*id)).collect()
                 })
-                .collect()),
-            None => Err(From::from("API error/no results")),
+                .await
+                .unwrap_or_else(|_| vec![None; ids.len()]);
+                for (id, xml) in ids.iter().zip(cached) {
+                    match xml.and_then(|xml| Self::parse_articles(&xml).ok()) {
+                        Some(cached) if !cached.is_empty() => articles.extend(cached),
+                        _ => missing.push(*id),
+                    }
+                }
+                if cache.is_offline() {
+                    missing.clear();
+                }
+            }
+            None => missing.clone_from(&ids),
+        }
+        for batch in missing.chunks(EFETCH_BATCH_SIZE) {
+            articles.extend(self.articles_batch(batch).await?);
+        }
+        Ok(Self::sort_by_ids(&ids, articles))
+    }
+
+    /// Fetches the articles for a large number of `ids` in chunks, with a
+    /// bounded number of concurrent requests. Each chunk is yielded as it
+    /// completes (or in input order, see [`ResultOrder`]); a failed chunk
+    /// is reported in its [`ChunkResult`] and does not stop the others.
+    /// Duplicate IDs are fetched once.
+    pub fn articles_stream<'a>(
+        &'a self,
+        ids: &[u64],
+        options: BulkFetchOptions,
+    ) -> BoxStream<'a, ChunkResult> {
+        let mut seen = HashSet::new();
+        let ids: Vec<u64> = ids.iter().copied().filter(|id| seen.insert(*id)).collect();
+        let chunks: Vec<Vec<u64>> = ids
+            .chunks(options.chunk_size.max(1))
+            .map(<[u64]>::to_vec)
+            .collect();
+        let mut progress = BulkProgress {
+            chunks_total: chunks.len(),
+            ..Default::default()
+        };
+        let requests =
+            stream::iter(chunks.into_iter().enumerate()).map(move |(index, ids)| async move {
+                let result = self.articles(&ids).await;
+                ChunkResult { index, ids, result }
+            });
+        let concurrency = options.concurrency.max(1);
+        let results = match options.order {
+            ResultOrder::Completion => requests.buffer_unordered(concurrency).boxed(),
+            ResultOrder::Input => requests.buffered(concurrency).boxed(),
+        };
+        let callback = options.progress;
+        results
+            .inspect(move |chunk| {
+                progress.chunks_done += 1;
+                match &chunk.result {
+                    Ok(articles) => progress.articles_done += articles.len(),
+                    Err(_) => progress.failed_chunks += 1,
+                }
+                if let Some(callback) = &callback {
+                    callback(&progress);
+                }
+            })
+            .boxed()
+    }
+
+    /// Runs [`Client::articles_stream`] to completion and collects the
+    /// articles, in input order, and the failed chunks.
+    pub async fn bulk_articles(&self, ids: &[u64], options: BulkFetchOptions) -> BulkFetchReport {
+        let mut chunks: Vec<ChunkResult> = self.articles_stream(ids, options).collect().await;
+        chunks.sort_by_key(|chunk| chunk.index);
+        let mut report = BulkFetchReport::default();
+        for chunk in chunks {
+            match chunk.result {
+                Ok(articles) => report.articles.extend(articles),
+                Err(e) => report.failures.push((chunk.ids, e)),
+            }
+        }
+        report
+    }
+
+    /// Orders `articles` to follow `ids`; articles without a PMID go last.
+    pub(crate) fn sort_by_ids(ids: &[u64], articles: Vec<PubmedArticle>) -> Vec<PubmedArticle> {
+        let mut by_pmid: HashMap<u64, PubmedArticle> = HashMap::new();
+        let mut unkeyed = vec![];
+        for article in articles {
+            match article.medline_citation.as_ref().map(|mc| mc.pmid) {
+                Some(pmid) if pmid != 0 => {
+                    by_pmid.insert(pmid, article);
+                }
+                _ => unkeyed.push(article),
+            }
         }
+        let mut ret: Vec<PubmedArticle> = ids.iter().filter_map(|id| by_pmid.remove(id)).collect();
+        ret.append(&mut unkeyed);
+        ret
     }
 
-    pub async fn articles(&self, ids: &[u64]) -> Result<Vec<PubmedArticle>, Box<dyn Error>> {
+    async fn articles_batch(&self, ids: &[u64]) -> Result<Vec<PubmedArticle>, PubmedError> {
+        let text = self
+            .ids_request("efetch.fcgi", &[("retmode", "xml")], ids)
+            .await?;
+        let articles = Self::parse_articles(&text)?;
+        if let Some(cache) = &self.cache {
+            let cache = cache.clone();
+            match tokio::task::spawn_blocking(move || cache.store(&text)).await {
+                Ok(Ok(_)) => {}
+                Ok(Err(e)) => eprintln!("PubMed: could not cache EFetch response: {e}"),
+                Err(e) => eprintln!("PubMed: could not cache EFetch response: {e}"),
+            }
+        }
+        Ok(articles)
+    }
+
+    /// Fetches ESummary document summaries for `ids`, in input order.
+    /// Batching and the switch to POST work as in [`Client::articles`].
+    pub async fn esummary(&self, ids: &[u64]) -> Result<Vec<DocumentSummary>, PubmedError> {
+        let mut ret = vec![];
+        for batch in ids.chunks(EFETCH_BATCH_SIZE) {
+            let text = self
+                .ids_request(
+                    "esummary.fcgi",
+                    &[("retmode", "json"), ("version", "2.0")],
+                    batch,
+                )
+                .await?;
+            let json: serde_json::Value = serde_json::from_str(&text)?;
+            match DocumentSummary::new_vec_from_json(&json) {
+                Some(summaries) => ret.extend(summaries),
+                None => return Err(PubmedError::unexpected("ESummary")),
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Retrieves ELink links of the given types for `ids`, keyed by source
+    /// PMID. Similar-article links carry relevance scores.
+    pub async fn links(
+        &self,
+        ids: &[u64],
+        link_names: &[LinkName],
+    ) -> Result<HashMap<u64, LinkSet>, PubmedError> {
+        let mut ret: HashMap<u64, LinkSet> = HashMap::new();
+        for link_name in link_names {
+            for batch in ids.chunks(EFETCH_POST_THRESHOLD) {
+                let mut form: Vec<(&str, String)> = vec![
+                    ("dbfrom", "pubmed".to_string()),
+                    ("db", link_name.target_db().to_string()),
+                    ("linkname", link_name.as_str().to_string()),
+                    ("cmd", "neighbor_score".to_string()),
+                    ("retmode", "json".to_string()),
+                ];
+                // One `id` parameter per PMID yields one link set per source
+                form.extend(batch.iter().map(|id| ("id", id.to_string())));
+                let text = self
+                    .send(HttpRequest::post(
+                        self.eutils_url("elink.fcgi", &[])?,
+                        &form,
+                    ))
+                    .await?;
+                let json: serde_json::Value = serde_json::from_str(&text)?;
+                let link_sets = LinkSet::new_vec_from_json(&json)
+                    .ok_or_else(|| PubmedError::unexpected("ELink"))?;
+                for link_set in link_sets {
+                    ret.entry(link_set.source_id)
+                        .or_insert_with(|| LinkSet {
+                            source_id: link_set.source_id,
+                            link_set_dbs: vec![],
+                        })
+                        .link_set_dbs
+                        .extend(link_set.link_set_dbs);
+                }
+            }
+        }
+        Ok(ret)
+    }
+
+    /// Retrieves EInfo statistics, search fields and link names for an
+    /// Entrez database, e.g. `"pubmed"`.
+    pub async fn einfo(&self, db: &str) -> Result<DatabaseInfo, PubmedError> {
+        let url = self.eutils_url(
+            "einfo.fcgi",
+            &[("db", db), ("version", "2.0"), ("retmode", "json")],
+        )?;
+        let json = self.get_json(url).await?;
+        DatabaseInfo::new_from_json(&json).ok_or_else(|| PubmedError::unexpected("EInfo"))
+    }
+
+    /// Lists the names of all Entrez databases.
+    pub async fn databases(&self) -> Result<Vec<String>, PubmedError> {
+        let url = self.eutils_url("einfo.fcgi", &[("retmode", "json")])?;
+        let json = self.get_json(url).await?;
+        DatabaseInfo::db_list_from_json(&json).ok_or_else(|| PubmedError::unexpected("EInfo"))
+    }
+
+    /// Resolves structured citations to PMIDs via ECitMatch. Returns one
+    /// [`CitationMatch`] per input key, in input order.
+    pub async fn citation_match(
+        &self,
+        keys: &[CitationKey],
+    ) -> Result<Vec<CitationMatch>, PubmedError> {
+        let mut ret = vec![];
+        for batch in keys.chunks(ECITMATCH_BATCH_SIZE) {
+            // Each row is sent with its position as key, as the caller's keys
+            // may repeat or be rewritten by `to_bdata`
+            let bdata: String = batch
+                .iter()
+                .enumerate()
+                .map(|(i, k)| k.to_bdata(&i.to_string()))
+                .collect::<Vec<_>>()
+                .join("\r");
+            let url = self.eutils_url(
+                "ecitmatch.cgi",
+                &[("db", "pubmed"), ("retmode", "xml"), ("bdata", &bdata)],
+            )?;
+            let text = self.send(HttpRequest::get(url)).await?;
+            let mut matches: HashMap<usize, CitationMatch> = text
+                .lines()
+                .filter_map(CitationMatch::new_from_line)
+                .filter_map(|m| Some((m.key.parse().ok()?, m)))
+                .collect();
+            ret.extend(batch.iter().enumerate().map(|(i, k)| {
+                let mut m = matches.remove(&i).unwrap_or_else(|| CitationMatch {
+                    key: String::new(),
+                    status: CitationMatchStatus::NotFound,
+                    detail: None,
+                });
+                m.key.clone_from(&k.key);
+                m
+            }));
+        }
+        Ok(ret)
+    }
+
+    /// Maps a mixed list of PMIDs, PMCIDs, DOIs and NIH manuscript IDs to
+    /// full ID records via the PMC ID Converter, in input order. IDs of an
+    /// unrecognized format or unknown to the converter come back with
+    /// `error` set.
+    pub async fn convert_ids<S: AsRef<str>>(
+        &self,
+        ids: &[S],
+    ) -> Result<Vec<IdRecord>, PubmedError> {
+        let ids: Vec<(&str, Option<(ConverterIdType, String)>)> = ids
+            .iter()
+            .map(|id| {
+                let id = id.as_ref().trim();
+                let detected = ConverterIdType::detect(id).map(|t| (t, t.normalize(id)));
+                (id, detected)
+            })
+            .collect();
+        let mut by_type: HashMap<ConverterIdType, Vec<&str>> = HashMap::new();
+        for (id_type, id) in ids.iter().filter_map(|(_, detected)| detected.as_ref()) {
+            by_type.entry(*id_type).or_default().push(id);
+        }
+        let mut records = vec![];
+        for (id_type, typed_ids) in by_type {
+            for batch in typed_ids.chunks(IDCONV_BATCH_SIZE) {
+                let mut params = vec![
+                    ("ids", batch.join(",")),
+                    ("idtype", id_type.as_str().to_string()),
+                    ("format", "json".to_string()),
+                    ("versions", "yes".to_string()),
+                    (
+                        "tool",
+                        self.tool.as_deref().unwrap_or("pubmed-rs").to_string(),
+                    ),
+                ];
+                if let Some(email) = &self.email {
+                    params.push(("email", email.to_string()));
+                }
+                let url = reqwest::Url::parse_with_params(&self.id_converter_url, &params)
+                    .map_err(|e| PubmedError::InvalidRequest(e.to_string()))?;
+                let json = self.get_json(url).await?;
+                let batch_records = IdRecord::new_vec_from_json(&json)
+                    .ok_or_else(|| PubmedError::unexpected("ID Converter"))?;
+                records.extend(batch_records.into_iter().map(|r| (id_type, r)));
+            }
+        }
+        let mut by_key: HashMap<(ConverterIdType, String), &IdRecord> = HashMap::new();
+        for (id_type, record) in &records {
+            for key in record.lookup_keys(*id_type) {
+                by_key.entry((*id_type, key)).or_insert(record);
+            }
+        }
+        Ok(ids
+            .iter()
+            .map(|(id, detected)| {
+                let found = detected.as_ref().and_then(|(id_type, normalized)| {
+                    by_key
+                        .get(&(*id_type, IdRecord::lookup_key(normalized)))
+                        .copied()
+                });
+                let mut record = match (detected, found) {
+                    (Some(_), Some(r)) => r.clone(),
+                    (Some(_), None) => IdRecord {
+                        error: Some("no record returned".to_string()),
+                        ..Default::default()
+                    },
+                    (None, _) => IdRecord {
+                        error: Some("unrecognized ID format".to_string()),
+                        ..Default::default()
+                    },
+                };
+                record.requested_id = (*id).to_string();
+                record
+            })
+            .collect())
+    }
+
+    /// Sends an ID-based E-utilities request for PubMed, via POST if there
+    /// are more than [`EFETCH_POST_THRESHOLD`] IDs, and returns the body.
+    async fn ids_request(
+        &self,
+        endpoint: &str,
+        params: &[(&str, &str)],
+        ids: &[u64],
+    ) -> Result<String, PubmedError> {
+        let use_post = ids.len() > EFETCH_POST_THRESHOLD;
         let ids: Vec<String> = ids.iter().map(std::string::ToString::to_string).collect();
-        let url = format!(
-            "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/efetch.fcgi?db=pubmed&retmode=xml&id={}{}",
-            ids.join(","),
-            self.api_key_param()
-        );
-        let text = reqwest::get(&url).await?.text().await?;
+        let ids = ids.join(",");
+        let params: Vec<(&str, &str)> = [("db", "pubmed")]
+            .into_iter()
+            .chain(params.iter().copied())
+            .chain([("id", ids.as_str())])
+            .collect();
+        let text = if use_post {
+            self.send(HttpRequest::post(self.eutils_url(endpoint, &[])?, &params))
+                .await?
+        } else {
+            self.send(HttpRequest::get(self.eutils_url(endpoint, &params)?))
+                .await?
+        };
+        Ok(text)
+    }
+
+    /// Runs an ESearch query with `usehistory=y` and returns a handle to the
+    /// result set on the History server, without transferring any IDs.
+    pub async fn search_history(&self, query: &str) -> Result<HistoryHandle, PubmedError> {
+        let url = self.eutils_url(
+            "esearch.fcgi",
+            &[
+                ("db", "pubmed"),
+                ("retmode", "json"),
+                ("usehistory", "y"),
+                ("retmax", "0"),
+                ("term", query),
+            ],
+        )?;
+        let json = self.get_json(url).await?;
+        HistoryHandle::new_from_esearch_json(&json)
+            .ok_or_else(|| PubmedError::unexpected("ESearch"))
+    }
+
+    /// Uploads a list of PMIDs to the History server via EPost.
+    pub async fn epost(&self, ids: &[u64]) -> Result<HistoryHandle, PubmedError> {
+        let ids: Vec<String> = ids.iter().map(std::string::ToString::to_string).collect();
+        let form = [("db", "pubmed".to_string()), ("id", ids.join(","))];
+        let text = self
+            .send(HttpRequest::post(
+                self.eutils_url("epost.fcgi", &[])?,
+                &form,
+            ))
+            .await?;
+        let doc = roxmltree::Document::parse(&text)?;
+        HistoryHandle::new_from_epost_xml(&doc.root(), ids.len() as u64).ok_or_else(|| {
+            PubmedError::Api {
+                message: format!("EPost failed: {text}"),
+                attempts: vec![],
+            }
+        })
+    }
+
+    /// Fetches `retmax` articles of a History server result set, starting at
+    /// `retstart`.
+    pub async fn articles_from_history(
+        &self,
+        handle: &HistoryHandle,
+        retstart: u64,
+        retmax: u64,
+    ) -> Result<Vec<PubmedArticle>, PubmedError> {
+        let url = self.eutils_url(
+            "efetch.fcgi",
+            &[
+                ("db", "pubmed"),
+                ("retmode", "xml"),
+                ("WebEnv", &handle.web_env),
+                ("query_key", &handle.query_key),
+                ("retstart", &retstart.to_string()),
+                ("retmax", &retmax.to_string()),
+            ],
+        )?;
+        let text = self.send(HttpRequest::get(url)).await?;
+        let articles = Self::parse_articles(&text)?;
+        Ok(articles)
+    }
+
+    /// Fetches every article of a History server result set, `chunk_size`
+    /// records per request.
+    pub async fn all_articles_from_history(
+        &self,
+        handle: &HistoryHandle,
+        chunk_size: u64,
+    ) -> Result<Vec<PubmedArticle>, PubmedError> {
+        let chunk_size = chunk_size.max(1);
+        let mut ret = vec![];
+        let mut retstart = 0;
+        while retstart < handle.count {
+            let articles = self
+                .articles_from_history(handle, retstart, chunk_size)
+                .await?;
+            if articles.is_empty() {
+                break;
+            }
+            ret.extend(articles);
+            retstart += chunk_size;
+        }
+        Ok(ret)
+    }
+
+    fn parse_articles(text: &str) -> Result<Vec<PubmedArticle>, PubmedError> {
         let parsing_options = ParsingOptions {
             allow_dtd: true,
             nodes_limit: u32::MAX,
             ..Default::default()
         };
-        let doc = roxmltree::Document::parse_with_options(&text, parsing_options)?;
-        tokio::time::sleep(self.get_sleep_time()).await; // To avoid being blocked by PubMed API
+        let doc = roxmltree::Document::parse_with_options(text, parsing_options)?;
         Ok(doc
             .root()
             .descendants()
@@ -91,19 +757,70 @@ impl Client {
             .collect())
     }
 
-    fn get_sleep_time(&self) -> std::time::Duration {
-        if self.api_key.is_some() {
-            std::time::Duration::from_millis(120) // 10/sec with api_key
-        } else {
-            std::time::Duration::from_millis(400) // 3/sec without api key
+    /// Sends `request` once the rate limiter allows it and returns the
+    /// response body, retrying according to the client's [`RetryPolicy`].
+    /// If all tries fail, the error lists each of them.
+    async fn send(&self, request: HttpRequest) -> Result<String, PubmedError> {
+        let mut attempts: Vec<Attempt> = vec![];
+        loop {
+            self.rate_limiter.acquire().await;
+            let (failure, retry_after) = match self.transport.send(&request).await {
+                Ok(response) => {
+                    let status = response.status;
+                    let retry_after = response
+                        .retry_after
+                        .as_deref()
+                        .and_then(RetryPolicy::parse_retry_after);
+                    let success = (200..300).contains(&status);
+                    match RetryPolicy::retry_reason(status, &response.body) {
+                        None if success => match RetryPolicy::api_error(&response.body) {
+                            None => return Ok(response.body),
+                            Some(message) => {
+                                let failure = Failure::Api(status, message);
+                                attempts.push(failure.attempt(None));
+                                return Err(failure.into_error(attempts));
+                            }
+                        },
+                        None => {
+                            attempts.push(Attempt {
+                                status: Some(status),
+                                reason: format!("HTTP {status}"),
+                                waited: None,
+                            });
+                            return Err(PubmedError::HttpStatus { status, attempts });
+                        }
+                        Some(reason) if success => (Failure::Api(status, reason), retry_after),
+                        Some(_) => (Failure::Status(status), retry_after),
+                    }
+                }
+                Err(PubmedError::Transport { source, .. })
+                    if source.is_timeout() || source.is_connect() =>
+                {
+                    (Failure::Transport(source), None)
+                }
+                Err(e) => return Err(e),
+            };
+            let tries = attempts.len() as u32 + 1;
+            let waited = (tries < self.retry_policy.max_attempts)
+                .then(|| self.retry_policy.delay(tries, retry_after));
+            attempts.push(failure.attempt(waited));
+            match waited {
+                Some(delay) => tokio::time::sleep(delay).await,
+                None => return Err(failure.into_error(attempts)),
+            }
         }
     }
 
-    pub async fn article(&self, id: u64) -> Result<PubmedArticle, Box<dyn Error>> {
+    async fn get_json(&self, url: reqwest::Url) -> Result<serde_json::Value, PubmedError> {
+        let text = self.send(HttpRequest::get(url)).await?;
+        Ok(serde_json::from_str(&text)?)
+    }
+
+    pub async fn article(&self, id: u64) -> Result<PubmedArticle, PubmedError> {
         match self.articles(&[id]).await?.pop() {
             Some(pubmed_article) => Ok(pubmed_article),
-            None => Err(From::from(format!(
-                "Can't find PubmedArticle for ID '{id}'"
+            None => Err(PubmedError::NotFound(format!(
+                "PubmedArticle for ID '{id}'"
             ))),
         }
     }
@@ -114,3 +831,295 @@ impl Default for Client {
         Self::new()
     }
 }
+
+fn default_base_url() -> String {
+    EUTILS_BASE_URL.to_string()
+}
+
+fn default_id_converter_url() -> String {
+    IDCONV_URL.to_string()
+}
+
+fn default_transport() -> Arc<dyn Transport> {
+    Arc::new(ReqwestTransport::default())
+}
+
+/// Configures a [`Client`]. NCBI asks heavy users to identify themselves
+/// with the `tool` and `email` parameters, which are added to every request.
+///
+/// `timeout`, `user_agent` and `proxy` configure the HTTP client the builder
+/// creates; they are ignored if a pre-configured one is passed via
+/// [`ClientBuilder::http_client`], or if a custom [`Transport`] is set.
+#[derive(Debug, Default)]
+pub struct ClientBuilder {
+    api_key: Option<String>,
+    api_key_source: ApiKeySource,
+    base_url: Option<String>,
+    id_converter_url: Option<String>,
+    tool: Option<String>,
+    email: Option<String>,
+    timeout: Option<Duration>,
+    user_agent: Option<String>,
+    proxy: Option<String>,
+    http: Option<reqwest::Client>,
+    transport: Option<Arc<dyn Transport>>,
+    rate_limit: Option<u32>,
+    retry_policy: Option<RetryPolicy>,
+    cache: Option<ArticleCache>,
+}
+
+impl ClientBuilder {
+    /// Sets the API key, taking precedence over any key found by
+    /// [`ClientBuilder::from_environment`].
+    #[must_use]
+    pub fn api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into()).filter(|k| !k.is_empty());
+        self.api_key_source = if self.api_key.is_some() {
+            ApiKeySource::Explicit
+        } else {
+            ApiKeySource::Unset
+        };
+        self
+    }
+
+    /// Fills in the API key, `tool` and `email` from the environment, see
+    /// [`EnvironmentConfig`]. Values set explicitly on the builder win.
+    #[must_use]
+    pub fn from_environment(self) -> Self {
+        self.with_environment_config(EnvironmentConfig::load())
+    }
+
+    pub(crate) fn with_environment_config(mut self, config: EnvironmentConfig) -> Self {
+        if self.api_key.is_none() && config.api_key.is_some() {
+            self.api_key = config.api_key;
+            self.api_key_source = config.api_key_source;
+        }
+        self.tool = self.tool.or(config.tool);
+        self.email = self.email.or(config.email);
+        self
+    }
+
+    /// The E-utilities base URL, e.g. a mirror or a local mock server.
+    /// Defaults to `https://eutils.ncbi.nlm.nih.gov/entrez/eutils/`.
+    #[must_use]
+    pub fn base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// The PMC ID Converter URL used by [`Client::convert_ids`], e.g. a
+    /// local mock server. Defaults to
+    /// `https://www.ncbi.nlm.nih.gov/pmc/utils/idconv/v1.0/`.
+    #[must_use]
+    pub fn id_converter_url(mut self, id_converter_url: impl Into<String>) -> Self {
+        self.id_converter_url = Some(id_converter_url.into());
+        self
+    }
+
+    #[must_use]
+    pub fn tool(mut self, tool: impl Into<String>) -> Self {
+        self.tool = Some(tool.into());
+        self
+    }
+
+    #[must_use]
+    pub fn email(mut self, email: impl Into<String>) -> Self {
+        self.email = Some(email.into());
+        self
+    }
+
+    /// Timeout for each HTTP request, from connecting to reading the body.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    #[must_use]
+    pub fn user_agent(mut self, user_agent: impl Into<String>) -> Self {
+        self.user_agent = Some(user_agent.into());
+        self
+    }
+
+    /// Routes all requests through the proxy at `proxy_url`.
+    #[must_use]
+    pub fn proxy(mut self, proxy_url: impl Into<String>) -> Self {
+        self.proxy = Some(proxy_url.into());
+        self
+    }
+
+    /// Uses a pre-configured HTTP client, e.g. to share its connection pool.
+    #[must_use]
+    pub fn http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = Some(http);
+        self
+    }
+
+    /// See [`Client::with_transport`].
+    #[must_use]
+    pub fn transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
+    /// See [`Client::with_rate_limit`].
+    #[must_use]
+    pub fn rate_limit(mut self, requests_per_second: u32) -> Self {
+        self.rate_limit = Some(requests_per_second);
+        self
+    }
+
+    /// See [`Client::with_retry_policy`].
+    #[must_use]
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// See [`Client::with_cache`].
+    #[must_use]
+    pub fn cache(mut self, cache: ArticleCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Creates the [`Client`]. Fails if the base URL, ID Converter URL or
+    /// proxy URL is invalid, or if the HTTP client can not be created.
+    pub fn build(self) -> Result<Client, PubmedError> {
+        let mut base_url = self.base_url.unwrap_or_else(default_base_url);
+        if !base_url.ends_with('/') {
+            base_url.push('/'); // Otherwise `Url::join` replaces the last segment
+        }
+        reqwest::Url::parse(&base_url)
+            .map_err(|e| PubmedError::InvalidRequest(format!("base URL '{base_url}': {e}")))?;
+        let id_converter_url = self
+            .id_converter_url
+            .unwrap_or_else(default_id_converter_url);
+        reqwest::Url::parse(&id_converter_url).map_err(|e| {
+            PubmedError::InvalidRequest(format!("ID Converter URL '{id_converter_url}': {e}"))
+        })?;
+        let transport: Arc<dyn Transport> = match (self.transport, self.http) {
+            (Some(transport), _) => transport,
+            (None, Some(http)) => Arc::new(ReqwestTransport::new(http)),
+            (None, None) => {
+                let mut builder = reqwest::Client::builder();
+                if let Some(timeout) = self.timeout {
+                    builder = builder.timeout(timeout);
+                }
+                if let Some(user_agent) = self.user_agent {
+                    builder = builder.user_agent(user_agent);
+                }
+                if let Some(proxy) = self.proxy {
+                    let proxy = reqwest::Proxy::all(&proxy).map_err(|e| {
+                        PubmedError::InvalidRequest(format!("proxy '{proxy}': {e}"))
+                    })?;
+                    builder = builder.proxy(proxy);
+                }
+                Arc::new(ReqwestTransport::new(builder.build()?))
+            }
+        };
+        let requests_per_second = self.rate_limit.unwrap_or(if self.api_key.is_some() {
+            API_KEY_REQUESTS_PER_SECOND
+        } else {
+            DEFAULT_REQUESTS_PER_SECOND
+        });
+        Ok(Client {
+            api_key: self.api_key,
+            api_key_source: self.api_key_source,
+            base_url,
+            id_converter_url,
+            tool: self.tool,
+            email: self.email,
+            transport,
+            rate_limiter: RateLimiter::new(requests_per_second),
+            retry_policy: self.retry_policy.unwrap_or_default(),
+            cache: self.cache,
+        })
+    }
+}
+
+/// Walks the pages of an ESearch query using `retstart`.
+#[derive(Debug)]
+pub struct SearchPager<'a> {
+    client: &'a Client,
+    query: String,
+    options: SearchOptions,
+    page_size: u64,
+    retstart: u64,
+    count: Option<u64>,
+}
+
+impl SearchPager<'_> {
+    /// Total number of hits, known after the first page has been fetched.
+    #[must_use]
+    pub fn total(&self) -> Option<u64> {
+        self.count
+    }
+
+    /// Fetches the next page, or returns `None` once all reachable results
+    /// have been returned.
+    pub async fn next_page(&mut self) -> Option<Result<SearchResult, PubmedError>> {
+        let limit = self.count.unwrap_or(u64::MAX).min(MAX_ESEARCH_RESULTS);
+        if self.retstart >= limit {
+            return None;
+        }
+        let retmax = self.page_size.min(limit - self.retstart);
+        let result = match self
+            .client
+            .search_with_options(&self.query, self.retstart, retmax, &self.options)
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                self.retstart = u64::MAX; // Stop after an error
+                return Some(Err(e));
+            }
+        };
+        self.count = Some(result.count);
+        self.retstart = if result.ids.is_empty() {
+            u64::MAX
+        } else {
+            self.retstart + result.ids.len() as u64
+        };
+        Some(Ok(result))
+    }
+
+    /// Fetches all remaining pages and returns the combined ID list.
+    pub async fn collect_ids(mut self) -> Result<Vec<u64>, PubmedError> {
+        let mut ids = vec![];
+        while let Some(page) = self.next_page().await {
+            ids.extend(page?.ids);
+        }
+        Ok(ids)
+    }
+}
+
+/// Why the last try of a request failed.
+enum Failure {
+    Transport(reqwest::Error),
+    Status(u16),
+    Api(u16, String),
+}
+
+impl Failure {
+    fn attempt(&self, waited: Option<Duration>) -> Attempt {
+        let (status, reason) = match self {
+            Failure::Transport(e) => (None, e.to_string()),
+            Failure::Status(status) => (Some(*status), format!("HTTP {status}")),
+            Failure::Api(status, message) => (Some(*status), message.to_string()),
+        };
+        Attempt {
+            status,
+            reason,
+            waited,
+        }
+    }
+
+    fn into_error(self, attempts: Vec<Attempt>) -> PubmedError {
+        match self {
+            Failure::Transport(source) => PubmedError::Transport { source, attempts },
+            Failure::Status(status) => PubmedError::HttpStatus { status, attempts },
+            Failure::Api(_, message) => PubmedError::Api { message, attempts },
+        }
+    }
+}
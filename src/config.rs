@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable holding the NCBI API key.
+pub const API_KEY_ENV_VAR: &str = "NCBI_API_KEY";
+
+/// Legacy key file, looked up in the current working directory.
+pub const API_KEY_FILE: &str = "ncbi_key";
+
+/// Where a [`crate::Client`] got its API key from.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApiKeySource {
+    /// No key was found; requests are limited to 3 per second
+    #[default]
+    Unset,
+    /// Passed to [`crate::Client::with_api_key`] or [`crate::ClientBuilder::api_key`]
+    Explicit,
+    /// The `NCBI_API_KEY` environment variable
+    Environment,
+    /// An `ncbi_key` file in the working directory
+    KeyFile(PathBuf),
+    /// The `api_key` entry of the config file
+    ConfigFile(PathBuf),
+}
+
+impl fmt::Display for ApiKeySource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ApiKeySource::Unset => write!(f, "no API key"),
+            ApiKeySource::Explicit => write!(f, "explicitly set"),
+            ApiKeySource::Environment => write!(f, "environment variable {API_KEY_ENV_VAR}"),
+            ApiKeySource::KeyFile(path) => write!(f, "key file {}", path.display()),
+            ApiKeySource::ConfigFile(path) => write!(f, "config file {}", path.display()),
+        }
+    }
+}
+
+/// Settings found in the environment. The API key is taken from the first
+/// of these that has one:
+///
+/// 1. the `NCBI_API_KEY` environment variable
+/// 2. an `ncbi_key` file in the working directory
+/// 3. the `api_key` entry of `$XDG_CONFIG_HOME/pubmed/config`, or
+///    `~/.config/pubmed/config` if `XDG_CONFIG_HOME` is not set
+///
+/// `tool` and `email` only come from the config file. It holds one
+/// `name = value` pair per line; lines starting with `#` are ignored.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnvironmentConfig {
+    pub api_key: Option<String>,
+    pub api_key_source: ApiKeySource,
+    pub tool: Option<String>,
+    pub email: Option<String>,
+}
+
+impl EnvironmentConfig {
+    #[must_use]
+    pub fn load() -> Self {
+        let config_file = Self::config_file_path();
+        Self::resolve(
+            env::var(API_KEY_ENV_VAR).ok(),
+            Path::new(API_KEY_FILE),
+            config_file.as_deref(),
+        )
+    }
+
+    /// The config file location, whether or not it exists.
+    #[must_use]
+    pub fn config_file_path() -> Option<PathBuf> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_home.join("pubmed").join("config"))
+    }
+
+    pub(crate) fn resolve(
+        env_key: Option<String>,
+        key_file: &Path,
+        config_file: Option<&Path>,
+    ) -> Self {
+        let mut ret = Self::default();
+        if let Some(path) = config_file {
+            if let Ok(text) = fs::read_to_string(path) {
+                for (name, value) in Self::parse_config(&text) {
+                    match name.as_str() {
+                        "api_key" => {
+                            ret.api_key = Some(value);
+                            ret.api_key_source = ApiKeySource::ConfigFile(path.to_path_buf());
+                        }
+                        "tool" => ret.tool = Some(value),
+                        "email" => ret.email = Some(value),
+                        x => eprintln!("PubMed: unknown config entry '{x}' in {}", path.display()),
+                    }
+                }
+            }
+        }
+        if let Some(key) = fs::read_to_string(key_file)
+            .ok()
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+        {
+            ret.api_key = Some(key);
+            ret.api_key_source = ApiKeySource::KeyFile(key_file.to_path_buf());
+        }
+        if let Some(key) = env_key
+            .map(|k| k.trim().to_string())
+            .filter(|k| !k.is_empty())
+        {
+            ret.api_key = Some(key);
+            ret.api_key_source = ApiKeySource::Environment;
+        }
+        ret
+    }
+
+    fn parse_config(text: &str) -> Vec<(String, String)> {
+        text.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(name, value)| {
+                let value = value.trim().trim_matches('"').trim();
+                (name.trim().to_lowercase(), value.to_string())
+            })
+            .filter(|(_, value)| !value.is_empty())
+            .collect()
+    }
+}
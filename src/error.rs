@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::retry::Attempt;
+
+/// Everything that can go wrong talking to PubMed. Variants that result from
+/// a request carry the history of all tries made, see [`crate::RetryPolicy`].
+#[derive(Debug)]
+pub enum PubmedError {
+    /// The request could not be sent or the response not received
+    Transport {
+        source: reqwest::Error,
+        attempts: Vec<Attempt>,
+    },
+    /// The server answered with a non-success HTTP status
+    HttpStatus { status: u16, attempts: Vec<Attempt> },
+    /// NCBI reported an error (or an empty result) in the response body
+    Api {
+        message: String,
+        attempts: Vec<Attempt>,
+    },
+    /// The response could not be parsed; `line` and `column` are 1-based
+    Parse {
+        message: String,
+        line: Option<u32>,
+        column: Option<u32>,
+    },
+    /// A requested record does not exist
+    NotFound(String),
+    /// The request could not be built, e.g. because of an invalid URL
+    InvalidRequest(String),
+}
+
+impl PubmedError {
+    /// The tries made before giving up, if this error came from a request.
+    #[must_use]
+    pub fn attempts(&self) -> &[Attempt] {
+        match self {
+            Self::Transport { attempts, .. }
+            | Self::HttpStatus { attempts, .. }
+            | Self::Api { attempts, .. } => attempts,
+            _ => &[],
+        }
+    }
+
+    /// Returns `true` if NCBI turned the request away for exceeding the
+    /// rate limit.
+    #[must_use]
+    pub fn is_rate_limited(&self) -> bool {
+        matches!(self, Self::HttpStatus { status: 429, .. })
+    }
+
+    /// A parse error for a response that is well-formed but lacks the
+    /// expected content.
+    pub(crate) fn unexpected(what: &str) -> Self {
+        Self::Parse {
+            message: format!("unexpected {what} response"),
+            line: None,
+            column: None,
+        }
+    }
+}
+
+impl fmt::Display for PubmedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Transport { source, .. } => write!(f, "transport error: {source}")?,
+            Self::HttpStatus { status, .. } => write!(f, "HTTP status {status}")?,
+            Self::Api { message, .. } => write!(f, "API error: {message}")?,
+            Self::Parse {
+                message,
+                line,
+                column,
+            } => {
+                write!(f, "parse error: {message}")?;
+                if let (Some(line), Some(column)) = (line, column) {
+                    write!(f, " at {line}:{column}")?;
+                }
+            }
+            Self::NotFound(what) => write!(f, "not found: {what}")?,
+            Self::InvalidRequest(message) => write!(f, "invalid request: {message}")?,
+        }
+        let attempts = self.attempts();
+        if attempts.len() > 1 {
+            write!(f, " (after {} attempts", attempts.len())?;
+            for (i, attempt) in attempts.iter().enumerate() {
+                write!(f, "; #{}: {}", i + 1, attempt.reason)?;
+                if let Some(waited) = attempt.waited {
+                    write!(f, ", retried after {}ms", waited.as_millis())?;
+                }
+            }
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for PubmedError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Transport { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for PubmedError {
+    fn from(source: reqwest::Error) -> Self {
+        Self::Transport {
+            source,
+            attempts: vec![],
+        }
+    }
+}
+
+impl From<roxmltree::Error> for PubmedError {
+    fn from(e: roxmltree::Error) -> Self {
+        let pos = e.pos();
+        Self::Parse {
+            message: e.to_string(),
+            line: Some(pos.row),
+            column: Some(pos.col),
+        }
+    }
+}
+
+impl From<serde_json::Error> for PubmedError {
+    fn from(e: serde_json::Error) -> Self {
+        Self::Parse {
+            message: e.to_string(),
+            line: u32::try_from(e.line()).ok().filter(|l| *l > 0),
+            column: u32::try_from(e.column()).ok().filter(|c| *c > 0),
+        }
+    }
+}
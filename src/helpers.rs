@@ -5,3 +5,28 @@ pub(crate) fn missing_tag_warning(s: &str) {
 
 #[cfg(not(debug_assertions))]
 pub(crate) fn missing_tag_warning(_s: &str) {}
+
+/// A non-empty JSON string, or a number as a string. E-utilities often use
+/// empty strings for missing values.
+pub(crate) fn json_string(value: &serde_json::Value) -> Option<String> {
+    match value {
+        serde_json::Value::String(s) if !s.is_empty() => Some(s.to_owned()),
+        serde_json::Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+/// The non-empty strings of a JSON array.
+pub(crate) fn json_strings(value: &serde_json::Value) -> Vec<String> {
+    value
+        .as_array()
+        .map(|a| a.iter().filter_map(json_string).collect())
+        .unwrap_or_default()
+}
+
+/// A JSON number, or a string holding one; E-utilities use both.
+pub(crate) fn json_u64(value: &serde_json::Value) -> Option<u64> {
+    value
+        .as_u64()
+        .or_else(|| value.as_str()?.trim().parse::<u64>().ok())
+}
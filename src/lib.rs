@@ -1,25 +1,90 @@
+pub mod bulk;
+pub mod cache;
 pub mod client;
+pub mod config;
+pub mod error;
 pub mod helpers;
+pub mod query;
+pub mod rate_limiter;
+pub mod retry;
+pub mod transport;
 pub mod types;
 
 // Re-export all public types at the crate root for backwards compatibility
-pub use client::Client;
+pub use bulk::{
+    BulkFetchOptions, BulkFetchReport, BulkProgress, ChunkResult, ProgressCallback, ResultOrder,
+};
+pub use cache::ArticleCache;
+pub use client::{
+    Client, ClientBuilder, SearchPager, ECITMATCH_BATCH_SIZE, EFETCH_BATCH_SIZE,
+    EFETCH_POST_THRESHOLD, MAX_ESEARCH_RESULTS,
+};
+pub use config::{ApiKeySource, EnvironmentConfig, API_KEY_ENV_VAR, API_KEY_FILE};
+pub use error::PubmedError;
+pub use query::{DateField, Field, Query, SearchDateType, SearchOptions, SortOrder};
+pub use rate_limiter::{RateLimiter, API_KEY_REQUESTS_PER_SECOND, DEFAULT_REQUESTS_PER_SECOND};
+pub use retry::{Attempt, RetryPolicy};
+pub use transport::{
+    HttpMethod, HttpRequest, HttpResponse, RecordingTransport, ReplayTransport, ReqwestTransport,
+    Transport, TransportFuture,
+};
 pub use types::*;
 
 #[cfg(test)]
 mod tests {
     use crate::types::pubmed_date::PubMedDate;
+    use crate::types::rich_text::{RichText, TextFormat};
 
     /// Helper: parse an XML string and return the root element node.
     fn root_element(xml: &str) -> roxmltree::Document<'_> {
         roxmltree::Document::parse(xml).unwrap()
     }
 
-    // ── Network-dependent integration tests ──────────────────────────
+    /// Helper: serves the recorded response in `tests/fixtures` if there
+    /// is one, otherwise queries PubMed and records it.
+    #[derive(Debug)]
+    struct FixtureTransport {
+        replay: crate::ReplayTransport,
+        record: crate::RecordingTransport,
+        rerecord: bool,
+    }
+
+    impl crate::Transport for FixtureTransport {
+        fn send<'a>(&'a self, request: &'a crate::HttpRequest) -> crate::TransportFuture<'a> {
+            Box::pin(async move {
+                if !self.rerecord {
+                    match self.replay.send(request).await {
+                        Err(crate::PubmedError::NotFound(_)) => {}
+                        result => return result,
+                    }
+                }
+                self.record.send(request).await
+            })
+        }
+    }
+
+    /// Helper: a client serving the recorded responses in `tests/fixtures`,
+    /// recording missing ones from PubMed. With `PUBMED_RECORD_FIXTURES`
+    /// set, all fixtures used are re-recorded, e.g.
+    /// `PUBMED_RECORD_FIXTURES=1 cargo test -- doi work date_parsing`.
+    fn replay_client() -> crate::Client {
+        let fixtures = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+        let live = std::sync::Arc::new(crate::ReqwestTransport::default());
+        crate::Client::builder()
+            .transport(std::sync::Arc::new(FixtureTransport {
+                replay: crate::ReplayTransport::new(fixtures),
+                record: crate::RecordingTransport::new(live, fixtures),
+                rerecord: std::env::var_os("PUBMED_RECORD_FIXTURES").is_some(),
+            }))
+            .build()
+            .unwrap()
+    }
+
+    // ── Integration tests, replayed from fixtures ────────────────────
 
     #[tokio::test]
     async fn doi() {
-        let client = crate::Client::new();
+        let client = replay_client();
         let ids = client
             .article_ids_from_query("\"10.1038/NATURE11174\"", 1000)
             .await
@@ -29,7 +94,7 @@ mod tests {
 
     #[tokio::test]
     async fn work() {
-        let client = crate::Client::new();
+        let client = replay_client();
         let article = client.article(22722859).await.unwrap();
         let date = article
             .medline_citation
@@ -44,7 +109,7 @@ mod tests {
 
     #[tokio::test]
     async fn date_parsing() {
-        let client = crate::Client::new();
+        let client = replay_client();
         let article = client.article(13777676).await.unwrap();
         let date = article
             .medline_citation
@@ -74,6 +139,9 @@ mod tests {
             minute: -1,
             date_type: None,
             pub_status: None,
+            medline_date: None,
+            season: None,
+            range: None,
         };
         assert_eq!(date.precision(), 0);
 
@@ -85,6 +153,9 @@ mod tests {
             minute: -1,
             date_type: None,
             pub_status: None,
+            medline_date: None,
+            season: None,
+            range: None,
         };
         assert_eq!(date.precision(), 9);
 
@@ -96,6 +167,9 @@ mod tests {
             minute: -1,
             date_type: None,
             pub_status: None,
+            medline_date: None,
+            season: None,
+            range: None,
         };
         assert_eq!(date.precision(), 10);
 
@@ -107,6 +181,9 @@ mod tests {
             minute: -1,
             date_type: None,
             pub_status: None,
+            medline_date: None,
+            season: None,
+            range: None,
         };
         assert_eq!(date.precision(), 11);
 
@@ -118,6 +195,9 @@ mod tests {
             minute: -1,
             date_type: None,
             pub_status: None,
+            medline_date: None,
+            season: None,
+            range: None,
         };
         assert_eq!(date.precision(), 12);
 
@@ -129,6 +209,9 @@ mod tests {
             minute: 30,
             date_type: None,
             pub_status: None,
+            medline_date: None,
+            season: None,
+            range: None,
         };
         assert_eq!(date.precision(), 13);
     }
@@ -277,7 +360,36 @@ mod tests {
         let doc = root_element(xml);
         let node = doc.root_element();
         let abs = crate::Abstract::new_from_xml(&node);
-        assert_eq!(abs.text.as_deref(), Some("This is the abstract text."));
+        assert_eq!(abs.text().as_deref(), Some("This is the abstract text."));
+        assert_eq!(abs.sections.len(), 1);
+        assert!(abs.sections[0].label.is_none());
+    }
+
+    #[test]
+    fn test_structured_abstract_from_xml() {
+        let xml = r#"<Abstract>
+            <AbstractText Label="BACKGROUND" NlmCategory="BACKGROUND">Why we did it.</AbstractText>
+            <AbstractText Label="METHODS" NlmCategory="METHODS">How, with <i>E. coli</i>.</AbstractText>
+            <AbstractText Label="CONCLUSIONS" NlmCategory="CONCLUSIONS">It worked.</AbstractText>
+            <CopyrightInformation>© 2020 The Authors.</CopyrightInformation>
+        </Abstract>"#;
+        let doc = root_element(xml);
+        let node = doc.root_element();
+        let abs = crate::Abstract::new_from_xml(&node);
+        assert_eq!(abs.sections.len(), 3);
+        assert_eq!(abs.sections[1].label.as_deref(), Some("METHODS"));
+        assert_eq!(abs.sections[1].nlm_category.as_deref(), Some("METHODS"));
+        assert_eq!(abs.sections[1].text.plain_text(), "How, with E. coli.");
+        assert_eq!(
+            abs.copyright_information.as_deref(),
+            Some("© 2020 The Authors.")
+        );
+        assert_eq!(
+            abs.text().as_deref(),
+            Some(
+                "BACKGROUND: Why we did it.\nMETHODS: How, with E. coli.\nCONCLUSIONS: It worked."
+            )
+        );
     }
 
     #[test]
@@ -286,7 +398,8 @@ mod tests {
         let doc = root_element(xml);
         let node = doc.root_element();
         let abs = crate::Abstract::new_from_xml(&node);
-        assert!(abs.text.is_none());
+        assert!(abs.text().is_none());
+        assert!(abs.copyright_information.is_none());
     }
 
     #[test]
@@ -469,10 +582,25 @@ mod tests {
         let node = doc.root_element();
         let a = crate::Article::new_from_xml(&node);
         assert_eq!(a.pub_model.as_deref(), Some("Print"));
-        assert_eq!(a.title.as_deref(), Some("My Article"));
+        assert_eq!(
+            a.title.as_ref().map(RichText::plain_text).as_deref(),
+            Some("My Article")
+        );
         assert_eq!(a.language.as_deref(), Some("eng"));
-        assert_eq!(a.vernacular_title.as_deref(), Some("Mon Article"));
-        assert_eq!(a.pagination.len(), 1);
+        assert_eq!(
+            a.vernacular_title
+                .as_ref()
+                .map(RichText::plain_text)
+                .as_deref(),
+            Some("Mon Article")
+        );
+        assert_eq!(
+            a.pagination
+                .as_ref()
+                .and_then(crate::Pagination::last_page)
+                .as_deref(),
+            Some("10")
+        );
         assert_eq!(a.e_location_ids.len(), 1);
         assert_eq!(a.e_location_ids[0].id.as_deref(), Some("10.1/test"));
         assert!(a.the_abstract.is_some());
@@ -540,11 +668,18 @@ mod tests {
         assert!(pd.article_ids.is_some());
         let ids = pd.article_ids.unwrap();
         assert_eq!(ids.ids.len(), 2);
-        assert_eq!(ids.ids[0].id_type.as_deref(), Some("pubmed"));
-        assert_eq!(ids.ids[1].id_type.as_deref(), Some("doi"));
+        assert_eq!(ids.ids[0].id_type, Some(crate::ArticleIdType::Pubmed));
+        assert_eq!(ids.ids[1].id_type, Some(crate::ArticleIdType::Doi));
         assert_eq!(pd.history.len(), 1);
         assert_eq!(pd.references.len(), 1);
-        assert_eq!(pd.references[0].citation.as_deref(), Some("Ref 1"));
+        assert_eq!(
+            pd.references[0]
+                .citation
+                .as_ref()
+                .map(RichText::plain_text)
+                .as_deref(),
+            Some("Ref 1")
+        );
     }
 
     #[test]
@@ -553,7 +688,10 @@ mod tests {
         let doc = root_element(xml);
         let node = doc.root_element();
         let r = crate::types::reference::Reference::new_from_xml(&node);
-        assert_eq!(r.citation.as_deref(), Some("Some paper"));
+        assert_eq!(
+            r.citation.as_ref().map(RichText::plain_text).as_deref(),
+            Some("Some paper")
+        );
         assert!(r.article_ids.is_some());
         assert_eq!(r.article_ids.unwrap().ids.len(), 1);
     }
@@ -564,10 +702,9 @@ mod tests {
         let doc = root_element(xml);
         let node = doc.root_element();
         let a = crate::Article::new_from_xml(&node);
-        assert_eq!(a.pagination.len(), 1);
-        match &a.pagination[0] {
-            crate::Pagination::MedlinePgn(s) => assert_eq!(s, "123-456"),
-        }
+        let pagination = a.pagination.unwrap();
+        assert_eq!(pagination.medline_pgn.as_deref(), Some("123-456"));
+        assert_eq!(pagination.page_count(), Some(334));
     }
 
     #[test]
@@ -575,7 +712,7 @@ mod tests {
         let article = crate::Article::new();
         assert!(article.pub_model.is_none());
         assert!(article.journal.is_none());
-        assert!(article.pagination.is_empty());
+        assert!(article.pagination.is_none());
 
         let journal = crate::Journal::new();
         assert!(journal.issn.is_none());
@@ -623,6 +760,9 @@ mod tests {
             minute: 30,
             date_type: Some("Electronic".to_string()),
             pub_status: Some("received".to_string()),
+            medline_date: None,
+            season: None,
+            range: None,
         };
         let json = serde_json::to_string(&date).unwrap();
         let date2: PubMedDate = serde_json::from_str(&json).unwrap();
@@ -641,7 +781,7 @@ mod tests {
         let json = serde_json::to_string(&article).unwrap();
         let article2: crate::Article = serde_json::from_str(&json).unwrap();
         assert!(article2.pub_model.is_none());
-        assert!(article2.pagination.is_empty());
+        assert!(article2.pagination.is_none());
     }
 
     #[test]
@@ -729,9 +869,9 @@ mod tests {
         let author = crate::Author::new_from_xml(&node);
         assert_eq!(author.identifiers.len(), 1);
         assert_eq!(author.identifiers[0].source.as_deref(), Some("ORCID"));
-        assert!(author.affiliation_info.is_some());
+        assert_eq!(author.affiliation_info.len(), 1);
         assert_eq!(
-            author.affiliation_info.unwrap().affiliation.as_deref(),
+            author.affiliation_info[0].affiliation.as_deref(),
             Some("MIT")
         );
     }
@@ -755,4 +895,1559 @@ mod tests {
         assert_eq!(gl.grants.len(), 1);
         assert_eq!(gl.grants[0].grant_id.as_deref(), Some("ABC"));
     }
+
+    #[test]
+    fn test_search_result_from_json() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{"esearchresult":{"count":"52013","retmax":"2","retstart":"20","idlist":["111","222"],
+            "querytranslation":"\"asthma\"[MeSH Terms]",
+            "warninglist":{"phrasesignored":["and"],"quotedphrasesnotfound":[],"outputmessages":[]},
+            "errorlist":{"phrasesnotfound":[],"fieldsnotfound":["[xx]"]}}}"#,
+        )
+        .unwrap();
+        let result = crate::SearchResult::new_from_json(&json).unwrap();
+        assert_eq!(result.count, 52013);
+        assert_eq!(result.ret_max, 2);
+        assert_eq!(result.ret_start, 20);
+        assert_eq!(result.ids, vec![111, 222]);
+        assert_eq!(
+            result.query_translation.as_deref(),
+            Some("\"asthma\"[MeSH Terms]")
+        );
+        assert_eq!(result.warnings, vec!["phrasesignored: and"]);
+        assert_eq!(result.errors, vec!["fieldsnotfound: [xx]"]);
+        assert!(result.is_truncated());
+    }
+
+    #[test]
+    fn test_search_result_from_json_error() {
+        let json: serde_json::Value =
+            serde_json::from_str(r#"{"esearchresult":{"ERROR":"Invalid query"}}"#).unwrap();
+        assert!(crate::SearchResult::new_from_json(&json).is_none());
+    }
+
+    #[test]
+    fn test_history_handle_from_esearch_json() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{"esearchresult":{"count":"52013","retmax":"0","retstart":"0","querykey":"1","webenv":"MCID_123","idlist":[]}}"#,
+        )
+        .unwrap();
+        let handle = crate::HistoryHandle::new_from_esearch_json(&json).unwrap();
+        assert_eq!(handle.web_env, "MCID_123");
+        assert_eq!(handle.query_key, "1");
+        assert_eq!(handle.count, 52013);
+    }
+
+    #[test]
+    fn test_history_handle_from_epost_xml() {
+        let xml = r#"<ePostResult><QueryKey>1</QueryKey><WebEnv>MCID_456</WebEnv></ePostResult>"#;
+        let doc = root_element(xml);
+        let handle = crate::HistoryHandle::new_from_epost_xml(&doc.root(), 3).unwrap();
+        assert_eq!(handle.web_env, "MCID_456");
+        assert_eq!(handle.query_key, "1");
+        assert_eq!(handle.count, 3);
+
+        let xml = r#"<ePostResult><ERROR>Invalid db name</ERROR></ePostResult>"#;
+        let doc = root_element(xml);
+        assert!(crate::HistoryHandle::new_from_epost_xml(&doc.root(), 3).is_none());
+    }
+
+    #[test]
+    fn test_client_sort_by_ids() {
+        let articles: Vec<crate::PubmedArticle> = [3u64, 1, 2]
+            .iter()
+            .map(|pmid| {
+                let xml = format!(
+                    "<PubmedArticle><MedlineCitation><PMID>{pmid}</PMID></MedlineCitation></PubmedArticle>"
+                );
+                let doc = root_element(&xml);
+                crate::PubmedArticle::new_from_xml(&doc.root_element())
+            })
+            .collect();
+        let sorted = crate::Client::sort_by_ids(&[2, 4, 3, 1], articles);
+        let pmids: Vec<u64> = sorted
+            .iter()
+            .map(|a| a.medline_citation.as_ref().unwrap().pmid)
+            .collect();
+        assert_eq!(pmids, vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn test_document_summary_from_json() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{"result":{"uids":["22722859","1"],
+            "22722859":{"uid":"22722859","pubdate":"2012 Jun 13","epubdate":"","source":"Nature",
+                "authors":[{"name":"Human Microbiome Project Consortium","authtype":"CollectiveName","clusterid":""},
+                           {"name":"Smith JA","authtype":"Author","clusterid":""}],
+                "lastauthor":"Smith JA","title":"Structure, function and diversity of the healthy human microbiome.",
+                "volume":"486","issue":"7402","pages":"207-14","lang":["eng"],"issn":"0028-0836","essn":"1476-4687",
+                "pubtype":["Journal Article"],"articleids":[{"idtype":"pubmed","idtypen":1,"value":"22722859"},
+                {"idtype":"doi","idtypen":3,"value":"10.1038/nature11234"}],
+                "history":[{"pubstatus":"received","date":"2012/02/13 00:00"},{"pubstatus":"entrez","date":"2012/06/22 06:00"}],
+                "pmcrefcount":1234,"fulljournalname":"Nature","sortpubdate":"2012/06/14 00:00"},
+            "1":{"uid":"1","error":"cannot get document summary"}}}"#,
+        )
+        .unwrap();
+        let summaries = crate::DocumentSummary::new_vec_from_json(&json).unwrap();
+        assert_eq!(summaries.len(), 1);
+        let ds = &summaries[0];
+        assert_eq!(ds.uid, 22722859);
+        assert_eq!(ds.source.as_deref(), Some("Nature"));
+        assert!(ds.epub_date.is_none());
+        assert_eq!(ds.authors.len(), 2);
+        assert_eq!(
+            ds.authors[0].collective_name.as_deref(),
+            Some("Human Microbiome Project Consortium")
+        );
+        assert_eq!(ds.authors[1].last_name.as_deref(), Some("Smith"));
+        assert_eq!(ds.authors[1].initials.as_deref(), Some("JA"));
+        assert_eq!(ds.article_ids.ids.len(), 2);
+        assert_eq!(
+            ds.article_ids.ids[1].id.as_deref(),
+            Some("10.1038/nature11234")
+        );
+        assert_eq!(ds.history.len(), 2);
+        assert_eq!(ds.history[0].pub_status.as_deref(), Some("received"));
+        assert_eq!(ds.history[1].hour, 6);
+        assert_eq!(ds.sort_pub_date.as_ref().unwrap().day, 14);
+        assert_eq!(ds.pmc_ref_count, Some(1234));
+    }
+
+    #[test]
+    fn test_link_sets_from_json() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{"linksets":[
+                {"dbfrom":"pubmed","ids":["22722859"],"linksetdbs":[{"dbto":"pubmed","linkname":"pubmed_pubmed",
+                    "links":[{"id":"22722859","score":"99999"},{"id":"22699609","score":48000}]}]},
+                {"dbfrom":"pubmed","ids":["123"],"linksetdbs":[{"dbto":"pmc","linkname":"pubmed_pmc","links":["3564958"]}]},
+                {"dbfrom":"pubmed","ids":["456"]}]}"#,
+        )
+        .unwrap();
+        let sets = crate::LinkSet::new_vec_from_json(&json).unwrap();
+        assert_eq!(sets.len(), 3);
+        let similar = sets[0].links(&crate::LinkName::SimilarArticles);
+        assert_eq!(sets[0].source_id, 22722859);
+        assert_eq!(similar.len(), 2);
+        assert_eq!(similar[1].id, 22699609);
+        assert_eq!(similar[1].score, Some(48000));
+        assert_eq!(sets[1].links(&crate::LinkName::Pmc)[0].id, 3564958);
+        assert_eq!(sets[1].links(&crate::LinkName::Pmc)[0].score, None);
+        assert!(sets[2].links(&crate::LinkName::CitedIn).is_empty());
+    }
+
+    #[test]
+    fn test_link_name_round_trip() {
+        for name in [
+            "pubmed_pubmed",
+            "pubmed_pubmed_citedin",
+            "pubmed_pubmed_refs",
+            "pubmed_pmc",
+            "pubmed_gene",
+        ] {
+            assert_eq!(crate::LinkName::from_name(name).as_str(), name);
+        }
+        assert_eq!(crate::LinkName::Pmc.target_db(), "pmc");
+        assert_eq!(crate::LinkName::CitedIn.target_db(), "pubmed");
+    }
+
+    #[test]
+    fn test_database_info_from_json() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{"einforesult":{"dbinfo":[{"dbname":"pubmed","menuname":"PubMed","description":"PubMed bibliographic record",
+                "dbbuild":"Build-2024.01.01","count":"36555123","lastupdate":"2024/01/01 05:51",
+                "fieldlist":[
+                    {"name":"AUTH","fullname":"Author","description":"Author(s) of publication","termcount":"17000000",
+                     "isdate":"N","isnumerical":"N","singletoken":"Y","hierarchy":"N","ishidden":"N"},
+                    {"name":"MESH","fullname":"MeSH Terms","description":"Medical Subject Headings","termcount":"900000",
+                     "isdate":"N","isnumerical":"N","singletoken":"Y","hierarchy":"Y","ishidden":"N"},
+                    {"name":"PDAT","fullname":"Publication Date","description":"Date of publication","termcount":"40000",
+                     "isdate":"Y","isnumerical":"N","singletoken":"Y","hierarchy":"N","ishidden":"N"}],
+                "linklist":[{"name":"pubmed_pubmed","menu":"Similar articles","description":"Similar PubMed articles","dbto":"pubmed"}]}]}}"#,
+        )
+        .unwrap();
+        let info = crate::DatabaseInfo::new_from_json(&json).unwrap();
+        assert_eq!(info.db_name, "pubmed");
+        assert_eq!(info.count, 36555123);
+        assert_eq!(info.last_update.as_ref().unwrap().year, 2024);
+        assert_eq!(info.fields.len(), 3);
+        assert!(info.fields[1].hierarchy);
+        assert!(info.fields[2].is_date);
+        assert_eq!(info.links[0].name, "pubmed_pubmed");
+        assert_eq!(info.field_for_tag("[au]").unwrap().name, "AUTH");
+        assert_eq!(info.field_for_tag("mh:noexp").unwrap().name, "MESH");
+        assert_eq!(info.field_for_tag("[MeSH Terms]").unwrap().name, "MESH");
+        assert_eq!(info.field_for_tag("dp").unwrap().name, "PDAT");
+        assert!(info.field_for_tag("[xx]").is_none());
+        assert_eq!(
+            info.unknown_field_tags("smith[au] AND asthma[mh] AND 2020[dp] AND foo[xx]"),
+            vec!["[xx]"]
+        );
+    }
+
+    #[test]
+    fn test_database_list_from_json() {
+        let json: serde_json::Value =
+            serde_json::from_str(r#"{"einforesult":{"dblist":["pubmed","pmc","gene"]}}"#).unwrap();
+        assert_eq!(
+            crate::DatabaseInfo::db_list_from_json(&json).unwrap(),
+            vec!["pubmed", "pmc", "gene"]
+        );
+    }
+
+    #[test]
+    fn test_citation_key_from_citation() {
+        let key = crate::CitationKey::from_citation(
+            "Mann BJ, Torian BE, Vedvick TS, Petri WA Jr. Sequence of a cysteine-rich galactose-specific lectin of Entamoeba histolytica. Proc Natl Acad Sci U S A. 1991;88(8):3248-52.",
+            "Art1",
+        )
+        .unwrap();
+        assert_eq!(key.journal.as_deref(), Some("Proc Natl Acad Sci U S A"));
+        assert_eq!(key.year.as_deref(), Some("1991"));
+        assert_eq!(key.volume.as_deref(), Some("88"));
+        assert_eq!(key.first_page.as_deref(), Some("3248"));
+        assert_eq!(key.author_name.as_deref(), Some("Mann BJ"));
+        assert_eq!(
+            key.to_bdata(&key.key),
+            "Proc Natl Acad Sci U S A|1991|88|3248|Mann BJ|Art1|"
+        );
+        assert!(crate::CitationKey::from_citation("Some free text", "x").is_none());
+
+        let key = crate::CitationKey::from_citation(
+            "Čaćić M, Smith J. Some title. J Foo. 2001;12:34-5.",
+            "k",
+        )
+        .unwrap();
+        assert_eq!(key.author_name.as_deref(), Some("Čaćić M"));
+        assert_eq!(key.year.as_deref(), Some("2001"));
+        assert!(crate::CitationKey::from_citation("Čaćić. Ćšž; 1:2.", "k").is_none());
+    }
+
+    #[test]
+    fn test_citation_match_from_line() {
+        let m = crate::CitationMatch::new_from_line(
+            "proc natl acad sci u s a|1991|88|3248|mann bj|Art1|2014248",
+        )
+        .unwrap();
+        assert_eq!(m.key, "Art1");
+        assert_eq!(m.pmid(), Some(2014248));
+
+        let m = crate::CitationMatch::new_from_line(
+            "science|1987|235|182|palmenberg ac|Art2|NOT_FOUND;INVALID_JOURNAL",
+        )
+        .unwrap();
+        assert_eq!(m.status, crate::CitationMatchStatus::NotFound);
+        assert_eq!(m.detail.as_deref(), Some("NOT_FOUND;INVALID_JOURNAL"));
+
+        let m = crate::CitationMatch::new_from_line(
+            "j biol chem|1998|||smith|Art3|AMBIGUOUS (12 citations)",
+        )
+        .unwrap();
+        assert_eq!(m.status, crate::CitationMatchStatus::Ambiguous);
+        assert!(crate::CitationMatch::new_from_line("garbage").is_none());
+    }
+
+    #[test]
+    fn test_spelling_suggestion_from_xml() {
+        let xml = r#"<eSpellResult><Database>pubmed</Database><Query>asthmaa OR alergies</Query><CorrectedQuery>asthma or allergies</CorrectedQuery><SpelledQuery><Original></Original><Replaced>asthma</Replaced><Original>or</Original><Replaced>allergies</Replaced></SpelledQuery><ERROR/></eSpellResult>"#;
+        let doc = root_element(xml);
+        let suggestion = crate::SpellingSuggestion::new_from_xml(&doc.root_element());
+        assert_eq!(suggestion.query, "asthmaa OR alergies");
+        assert_eq!(
+            suggestion.corrected_query.as_deref(),
+            Some("asthma or allergies")
+        );
+        assert!(suggestion.has_correction());
+        let replacements = suggestion.replacements();
+        assert_eq!(replacements.len(), 2);
+        assert_eq!(replacements[0].original.as_deref(), Some("asthmaa"));
+        assert_eq!(replacements[0].replacement, "asthma");
+        assert_eq!(replacements[1].original.as_deref(), Some("alergies"));
+        assert_eq!(replacements[1].replacement, "allergies");
+    }
+
+    #[test]
+    fn test_spelling_suggestion_without_correction() {
+        let xml = r#"<eSpellResult><Database>pubmed</Database><Query>asthma</Query><CorrectedQuery></CorrectedQuery><SpelledQuery/><ERROR/></eSpellResult>"#;
+        let doc = root_element(xml);
+        let suggestion = crate::SpellingSuggestion::new_from_xml(&doc.root_element());
+        assert!(!suggestion.has_correction());
+        assert!(suggestion.replacements().is_empty());
+    }
+
+    #[test]
+    fn test_converter_id_type_detect() {
+        use crate::ConverterIdType;
+        assert_eq!(
+            ConverterIdType::detect("23193287"),
+            Some(ConverterIdType::Pmid)
+        );
+        assert_eq!(
+            ConverterIdType::detect("PMC3531190"),
+            Some(ConverterIdType::Pmcid)
+        );
+        assert_eq!(
+            ConverterIdType::detect("pmc3531190"),
+            Some(ConverterIdType::Pmcid)
+        );
+        assert_eq!(
+            ConverterIdType::detect("10.1093/nar/gks1195"),
+            Some(ConverterIdType::Doi)
+        );
+        assert_eq!(
+            ConverterIdType::detect("NIHMS1677310"),
+            Some(ConverterIdType::Mid)
+        );
+        for doi in [
+            "doi:10.1093/nar/gks1195",
+            "https://doi.org/10.1093/NAR/gks1195",
+        ] {
+            assert_eq!(ConverterIdType::detect(doi), Some(ConverterIdType::Doi));
+            assert_eq!(ConverterIdType::Doi.normalize(doi), "10.1093/nar/gks1195");
+        }
+        assert_eq!(ConverterIdType::detect("foo"), None);
+    }
+
+    #[test]
+    fn test_id_records_from_json() {
+        let json: serde_json::Value = serde_json::from_str(
+            r#"{"status":"ok","records":[
+                {"pmcid":"PMC3531190","pmid":"23193287","doi":"10.1093/nar/gks1195",
+                 "versions":[{"pmcid":"PMC3531190.1","current":"true"}]},
+                {"pmcid":"PMC9999999","pmid":36000000,"mid":"NIHMS1677310","live":"false","release-date":"2027/01/01",
+                 "versions":[{"pmcid":"PMC9999999.1","mid":"NIHMS1677310","current":"true","live":"false"}]},
+                {"requested-id":"PMC0","status":"error","errmsg":"invalid article id"}]}"#,
+        )
+        .unwrap();
+        let records = crate::IdRecord::new_vec_from_json(&json).unwrap();
+        use crate::ConverterIdType::{Doi, Mid, Pmcid};
+        let matches = |record: &crate::IdRecord, id_type, id: &str| {
+            record
+                .lookup_keys(id_type)
+                .contains(&crate::IdRecord::lookup_key(id))
+        };
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[0].pmid, Some(23193287));
+        assert_eq!(records[0].versions.len(), 1);
+        assert!(records[0].versions[0].current);
+        assert!(matches(&records[0], Doi, "10.1093/NAR/GKS1195"));
+        assert!(matches(&records[0], Pmcid, "3531190"));
+        assert_eq!(records[1].pmid, Some(36000000));
+        assert_eq!(records[1].live, Some(false));
+        assert_eq!(records[1].release_date.as_deref(), Some("2027/01/01"));
+        assert!(matches(&records[1], Mid, "nihms1677310"));
+        assert_eq!(records[2].error.as_deref(), Some("invalid article id"));
+        assert!(matches(&records[2], Pmcid, "PMC0"));
+    }
+
+    #[test]
+    fn test_article_id_list_accessors() {
+        let xml = r#"<ArticleIdList>
+            <ArticleId IdType="pubmed">22722859</ArticleId>
+            <ArticleId IdType="doi">10.1038/NATURE11234</ArticleId>
+            <ArticleId IdType="pmc">3564958</ArticleId>
+            <ArticleId IdType="pii">nature11234</ArticleId>
+            <ArticleId IdType="mid">NIHMS432648</ArticleId>
+            <ArticleId IdType="bookaccession">NBK1</ArticleId>
+        </ArticleIdList>"#;
+        let doc = root_element(xml);
+        let ids = crate::ArticleIdList::new_from_xml(&doc.root_element());
+        assert_eq!(ids.pmid(), Some(22722859));
+        assert_eq!(ids.doi().as_deref(), Some("10.1038/nature11234"));
+        assert_eq!(ids.pmcid().as_deref(), Some("PMC3564958"));
+        assert_eq!(ids.pii().as_deref(), Some("nature11234"));
+        assert_eq!(ids.mid().as_deref(), Some("NIHMS432648"));
+        assert_eq!(
+            ids.ids[5].id_type,
+            Some(crate::ArticleIdType::Other("bookaccession".to_string()))
+        );
+        let json = serde_json::to_string(&ids.ids[1]).unwrap();
+        assert_eq!(json, r#"{"id_type":"doi","id":"10.1038/NATURE11234"}"#);
+        let id: crate::ArticleId = serde_json::from_str(&json).unwrap();
+        assert_eq!(id.id_type, Some(crate::ArticleIdType::Doi));
+    }
+
+    #[test]
+    fn test_article_id_type_normalize() {
+        use crate::ArticleIdType;
+        assert_eq!(
+            ArticleIdType::Doi.normalize("https://doi.org/10.1/ABC"),
+            "10.1/abc"
+        );
+        assert_eq!(ArticleIdType::Doi.normalize("doi: 10.1/ABC"), "10.1/abc");
+        assert_eq!(ArticleIdType::Pmc.normalize("pmc123"), "PMC123");
+        assert_eq!(ArticleIdType::Pmc.normalize("PMC123"), "PMC123");
+        assert_eq!(ArticleIdType::Pii.normalize(" S0140 "), "S0140");
+    }
+
+    #[test]
+    fn test_reference_and_elocation_id_accessors() {
+        let xml = r#"<Reference><Citation>Some paper</Citation><ArticleIdList><ArticleId IdType="doi">10.1/REF</ArticleId><ArticleId IdType="pmc">PMC42</ArticleId></ArticleIdList></Reference>"#;
+        let doc = root_element(xml);
+        let r = crate::types::reference::Reference::new_from_xml(&doc.root_element());
+        assert_eq!(r.doi().as_deref(), Some("10.1/ref"));
+        assert_eq!(r.pmcid().as_deref(), Some("PMC42"));
+        assert!(r.pmid().is_none());
+
+        let xml = r#"<ELocationID EIdType="doi" ValidYN="Y">10.1234/TEST</ELocationID>"#;
+        let doc = root_element(xml);
+        let eloc = crate::ELocationID::new_from_xml(&doc.root_element());
+        assert_eq!(eloc.id_type(), Some(crate::ArticleIdType::Doi));
+        assert_eq!(eloc.doi().as_deref(), Some("10.1234/test"));
+        assert!(eloc.pii().is_none());
+    }
+
+    #[test]
+    fn test_eutils_url_encoding() {
+        let client = crate::Client::with_api_key("k");
+        let url = client
+            .eutils_url(
+                "esearch.fcgi",
+                &[("db", "pubmed"), ("term", "C# & C++ \"Müller\"[au]")],
+            )
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/esearch.fcgi?db=pubmed&term=C%23+%26+C%2B%2B+%22M%C3%BCller%22%5Bau%5D&api_key=k"
+        );
+    }
+
+    #[test]
+    fn test_query_builder() {
+        use crate::{DateField, Field, Query};
+        let q = Query::mesh("Asthma")
+            .and(Query::field("Smith J", Field::Author))
+            .and(Query::date_range(DateField::Publication, "2020", "2022"));
+        assert_eq!(
+            q.to_string(),
+            r#""Asthma"[mh] AND "Smith J"[au] AND 2020:2022[dp]"#
+        );
+
+        let q = Query::term("cancer")
+            .or(Query::mesh_no_explode("Neoplasms"))
+            .and(Query::proximity("hip pain", Field::TitleAbstract, 2))
+            .not(Query::field("Review", Field::PublicationType));
+        assert_eq!(
+            q.to_string(),
+            r#"((cancer OR "Neoplasms"[mh:noexp]) AND "hip pain"[tiab:~2]) NOT Review[pt]"#
+        );
+
+        assert_eq!(Query::mesh_major("Asthma").to_string(), r#""Asthma"[majr]"#);
+        assert_eq!(Query::term("OR").to_string(), r#""OR""#);
+        assert_eq!(Query::term("canc*").to_string(), "canc*");
+        assert_eq!(
+            Query::field("10.1038/NATURE11174", Field::LocationId).to_string(),
+            r#""10.1038/NATURE11174"[lid]"#
+        );
+        assert_eq!(
+            Query::field("a \"b\" c", Field::Title).to_string(),
+            r#""a b c"[ti]"#
+        );
+        assert_eq!(
+            Query::raw("x[au] OR y[au]")
+                .and(Query::term("z"))
+                .to_string(),
+            "(x[au] OR y[au]) AND z"
+        );
+    }
+
+    #[test]
+    fn test_search_options_params() {
+        use crate::{SearchDateType, SearchOptions, SortOrder};
+        assert!(SearchOptions::default().to_params().is_empty());
+
+        let options = SearchOptions {
+            sort: Some(SortOrder::PublicationDate),
+            date_type: Some(SearchDateType::Entrez),
+            rel_date: Some(7),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.to_params(),
+            vec![
+                ("sort", "pub_date".to_string()),
+                ("datetype", "edat".to_string()),
+                ("reldate", "7".to_string()),
+            ]
+        );
+
+        let options = SearchOptions {
+            sort: Some(SortOrder::JournalName),
+            date_type: Some(SearchDateType::Publication),
+            min_date: Some("2020/01/01".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(
+            options.to_params(),
+            vec![
+                ("sort", "JournalName".to_string()),
+                ("datetype", "pdat".to_string()),
+                ("mindate", "2020/01/01".to_string()),
+                ("maxdate", "3000".to_string()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_shared_across_clones() {
+        let client = crate::Client::with_api_key("test").with_rate_limit(50);
+        assert_eq!(client.rate_limiter().requests_per_second(), 50);
+        let clone = client.clone();
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            client.rate_limiter().acquire().await;
+            clone.rate_limiter().acquire().await;
+        }
+        // The first request is free, the other five wait 20ms each
+        assert!(start.elapsed() >= std::time::Duration::from_millis(95));
+
+        let client = crate::Client::with_api_key("");
+        assert_eq!(
+            client.rate_limiter().requests_per_second(),
+            crate::DEFAULT_REQUESTS_PER_SECOND
+        );
+    }
+
+    #[test]
+    fn test_retry_reason() {
+        use crate::RetryPolicy;
+        assert_eq!(
+            RetryPolicy::retry_reason(429, ""),
+            Some("HTTP 429".to_string())
+        );
+        assert_eq!(
+            RetryPolicy::retry_reason(502, "Bad Gateway"),
+            Some("HTTP 502".to_string())
+        );
+        assert_eq!(RetryPolicy::retry_reason(404, ""), None);
+        assert_eq!(
+            RetryPolicy::retry_reason(200, "  "),
+            Some("empty response".to_string())
+        );
+        assert_eq!(
+            RetryPolicy::retry_reason(
+                200,
+                "<eFetchResult><ERROR>Unable to obtain query #1</ERROR></eFetchResult>"
+            ),
+            None
+        );
+        assert_eq!(
+            RetryPolicy::api_error(
+                "<eFetchResult><ERROR>Unable to obtain query #1</ERROR></eFetchResult>"
+            ),
+            Some("Unable to obtain query #1".to_string())
+        );
+        assert_eq!(
+            RetryPolicy::retry_reason(200, r#"{"esearchresult":{"ERROR":"Invalid db name"}}"#),
+            None
+        );
+        assert_eq!(
+            RetryPolicy::api_error(r#"{"esearchresult":{"ERROR":"Invalid db name"}}"#),
+            Some("Invalid db name".to_string())
+        );
+        assert_eq!(
+            RetryPolicy::retry_reason(
+                200,
+                r#"{"esearchresult":{"ERROR":"Search Backend failed"}}"#
+            ),
+            Some("Search Backend failed".to_string())
+        );
+        assert_eq!(
+            RetryPolicy::retry_reason(200, r#"{"header":{},"esearchresult":{}}"#),
+            Some("empty eSearchResult".to_string())
+        );
+        assert_eq!(
+            RetryPolicy::retry_reason(200, "<eSearchResult></eSearchResult>"),
+            Some("empty eSearchResult".to_string())
+        );
+        assert_eq!(
+            RetryPolicy::retry_reason(
+                200,
+                r#"{"esearchresult":{"count":"0","retmax":"0","idlist":[]}}"#
+            ),
+            None
+        );
+        assert_eq!(
+            RetryPolicy::retry_reason(200, "<PubmedArticleSet></PubmedArticleSet>"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_retry_policy_delay() {
+        use crate::{Attempt, PubmedError, RetryPolicy};
+        use std::time::Duration;
+        let policy = RetryPolicy {
+            jitter: false,
+            ..Default::default()
+        };
+        assert_eq!(policy.delay(1, None), Duration::from_millis(500));
+        assert_eq!(policy.delay(3, None), Duration::from_secs(2));
+        assert_eq!(policy.delay(10, None), Duration::from_secs(8));
+        assert_eq!(
+            policy.delay(1, Some(Duration::from_secs(3))),
+            Duration::from_secs(3)
+        );
+        let jittered = RetryPolicy::default().delay(2, None);
+        assert!(jittered >= Duration::from_millis(500) && jittered <= Duration::from_secs(1));
+        assert_eq!(
+            RetryPolicy::parse_retry_after(" 5 "),
+            Some(Duration::from_secs(5))
+        );
+        assert_eq!(
+            RetryPolicy::parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            None
+        );
+
+        let error = PubmedError::HttpStatus {
+            status: 429,
+            attempts: vec![
+                Attempt {
+                    status: Some(503),
+                    reason: "HTTP 503".to_string(),
+                    waited: Some(Duration::from_millis(500)),
+                },
+                Attempt {
+                    status: Some(429),
+                    reason: "HTTP 429".to_string(),
+                    waited: None,
+                },
+            ],
+        };
+        assert_eq!(
+            error.to_string(),
+            "HTTP status 429 (after 2 attempts; #1: HTTP 503, retried after 500ms; #2: HTTP 429)"
+        );
+        assert!(error.is_rate_limited());
+        assert_eq!(error.attempts().len(), 2);
+    }
+
+    #[test]
+    fn test_pubmed_error() {
+        use crate::PubmedError;
+        fn assert_send_sync<T: Send + Sync + std::error::Error + 'static>() {}
+        assert_send_sync::<PubmedError>();
+
+        let error: PubmedError = roxmltree::Document::parse("<a>\n<b></a>")
+            .unwrap_err()
+            .into();
+        match &error {
+            PubmedError::Parse { line, column, .. } => {
+                assert_eq!(*line, Some(2));
+                assert!(column.is_some());
+            }
+            e => panic!("expected a parse error, got {e:?}"),
+        }
+        assert!(error.to_string().starts_with("parse error: "));
+
+        let error: PubmedError = serde_json::from_str::<serde_json::Value>("{\n  \"a\": }")
+            .unwrap_err()
+            .into();
+        assert!(matches!(error, PubmedError::Parse { line: Some(2), .. }));
+
+        let error = PubmedError::NotFound("PubmedArticle for ID '1'".to_string());
+        assert!(error.attempts().is_empty());
+        assert_eq!(error.to_string(), "not found: PubmedArticle for ID '1'");
+    }
+
+    #[test]
+    fn test_client_builder() {
+        let client = crate::Client::builder()
+            .base_url("http://localhost:8080/eutils")
+            .tool("my-tool")
+            .email("me@example.org")
+            .api_key("k")
+            .timeout(std::time::Duration::from_secs(5))
+            .user_agent("my-tool/1.0")
+            .build()
+            .unwrap();
+        let url = client
+            .eutils_url("esearch.fcgi", &[("db", "pubmed")])
+            .unwrap();
+        assert_eq!(
+            url.as_str(),
+            "http://localhost:8080/eutils/esearch.fcgi?db=pubmed&tool=my-tool&email=me%40example.org&api_key=k"
+        );
+        assert_eq!(
+            client.rate_limiter().requests_per_second(),
+            crate::API_KEY_REQUESTS_PER_SECOND
+        );
+
+        let client = crate::Client::builder()
+            .http_client(reqwest::Client::new())
+            .rate_limit(20)
+            .build()
+            .unwrap();
+        assert_eq!(client.rate_limiter().requests_per_second(), 20);
+        assert!(client
+            .eutils_url("einfo.fcgi", &[])
+            .unwrap()
+            .as_str()
+            .starts_with("https://eutils.ncbi.nlm.nih.gov/entrez/eutils/einfo.fcgi"));
+
+        assert!(matches!(
+            crate::Client::builder().base_url("not a url").build(),
+            Err(crate::PubmedError::InvalidRequest(_))
+        ));
+        assert!(matches!(
+            crate::Client::builder().proxy("::").build(),
+            Err(crate::PubmedError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_environment_config_precedence() {
+        use crate::{ApiKeySource, EnvironmentConfig};
+        let dir = std::env::temp_dir().join(format!("pubmed_config_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key_file = dir.join("ncbi_key");
+        let config_file = dir.join("config");
+        std::fs::write(
+            &config_file,
+            "# NCBI settings\napi_key = config-key\ntool = \"my-tool\"\nemail=me@example.org\n",
+        )
+        .unwrap();
+
+        let config = EnvironmentConfig::resolve(None, &key_file, Some(&config_file));
+        assert_eq!(config.api_key.as_deref(), Some("config-key"));
+        assert_eq!(
+            config.api_key_source,
+            ApiKeySource::ConfigFile(config_file.clone())
+        );
+        assert_eq!(config.tool.as_deref(), Some("my-tool"));
+        assert_eq!(config.email.as_deref(), Some("me@example.org"));
+
+        std::fs::write(&key_file, " file-key\n").unwrap();
+        let config = EnvironmentConfig::resolve(None, &key_file, Some(&config_file));
+        assert_eq!(config.api_key.as_deref(), Some("file-key"));
+        assert_eq!(
+            config.api_key_source,
+            ApiKeySource::KeyFile(key_file.clone())
+        );
+        assert_eq!(config.tool.as_deref(), Some("my-tool"));
+
+        let config =
+            EnvironmentConfig::resolve(Some("env-key".to_string()), &key_file, Some(&config_file));
+        assert_eq!(config.api_key.as_deref(), Some("env-key"));
+        assert_eq!(config.api_key_source, ApiKeySource::Environment);
+        assert_eq!(
+            config.api_key_source.to_string(),
+            "environment variable NCBI_API_KEY"
+        );
+
+        let client = crate::Client::builder()
+            .api_key("explicit-key")
+            .with_environment_config(config.clone())
+            .build()
+            .unwrap();
+        assert_eq!(client.api_key_source(), &ApiKeySource::Explicit);
+        let client = crate::Client::builder()
+            .with_environment_config(config)
+            .build()
+            .unwrap();
+        assert_eq!(client.api_key_source(), &ApiKeySource::Environment);
+
+        let config = EnvironmentConfig::resolve(Some(" ".to_string()), &dir.join("missing"), None);
+        assert_eq!(config.api_key, None);
+        assert_eq!(config.api_key_source, ApiKeySource::Unset);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_article_cache() {
+        use crate::ArticleCache;
+        let dir = std::env::temp_dir().join(format!("pubmed_cache_test_{}", std::process::id()));
+        let article = |pmid: u64, year: u32, title: &str| {
+            format!(
+                "<PubmedArticle><MedlineCitation><PMID>{pmid}</PMID>\
+                 <DateRevised><Year>{year}</Year><Month>01</Month><Day>02</Day></DateRevised>\
+                 <Article><ArticleTitle>{title}</ArticleTitle></Article>\
+                 </MedlineCitation></PubmedArticle>"
+            )
+        };
+        let response = format!(
+            "<?xml version=\"1.0\"?>\n<!DOCTYPE PubmedArticleSet>\n<PubmedArticleSet>{}{}</PubmedArticleSet>",
+            article(1001, 2020, "One"),
+            article(2002, 2020, "Two")
+        );
+        let cache = ArticleCache::new(&dir).with_offline(true);
+        assert_eq!(cache.store(&response).unwrap(), 2);
+        assert_eq!(cache.get(1001), Some(article(1001, 2020, "One")));
+        assert_eq!(cache.get(3003), None);
+
+        // An older revision does not replace a newer one
+        let older = format!(
+            "<PubmedArticleSet>{}</PubmedArticleSet>",
+            article(1001, 2019, "Old")
+        );
+        assert_eq!(cache.store(&older).unwrap(), 0);
+        let newer = format!(
+            "<PubmedArticleSet>{}</PubmedArticleSet>",
+            article(1001, 2021, "New")
+        );
+        assert_eq!(cache.store(&newer).unwrap(), 1);
+
+        // Offline, the client serves cached PMIDs and skips the rest
+        let client = crate::Client::with_api_key("").with_cache(cache.clone());
+        let articles = client.articles(&[3003, 2002, 1001]).await.unwrap();
+        let pmids: Vec<u64> = articles
+            .iter()
+            .map(|a| a.medline_citation.as_ref().unwrap().pmid)
+            .collect();
+        assert_eq!(pmids, vec![2002, 1001]);
+
+        let expired = cache.clone().with_ttl(std::time::Duration::ZERO);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        assert!(
+            expired.get(1001).is_some(),
+            "offline serves expired records"
+        );
+        assert_eq!(expired.with_offline(false).get(1001), None);
+
+        // The size cap evicts the oldest records
+        let capped = ArticleCache::new(&dir).with_max_size(300);
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        let third = format!(
+            "<PubmedArticleSet>{}</PubmedArticleSet>",
+            article(3003, 2020, "Three")
+        );
+        assert_eq!(capped.store(&third).unwrap(), 1);
+        assert_eq!(capped.get(2002), None);
+        assert!(capped.get(3003).is_some());
+
+        cache.clear().unwrap();
+        assert_eq!(cache.get(3003), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[derive(Debug)]
+    struct EchoTransport;
+
+    impl crate::Transport for EchoTransport {
+        fn send<'a>(&'a self, request: &'a crate::HttpRequest) -> crate::TransportFuture<'a> {
+            Box::pin(async move {
+                Ok(crate::HttpResponse {
+                    status: 200,
+                    retry_after: None,
+                    body: format!(
+                        "<eInfoResult><DbList><DbName>{}</DbName></DbList></eInfoResult>",
+                        request.form.len()
+                    ),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay_transport() {
+        use crate::{HttpRequest, RecordingTransport, ReplayTransport, Transport};
+        let dir = std::env::temp_dir().join(format!("pubmed_fixture_test_{}", std::process::id()));
+        let url = |key: &str| {
+            reqwest::Url::parse(&format!(
+                "https://eutils.ncbi.nlm.nih.gov/entrez/eutils/epost.fcgi?api_key={key}"
+            ))
+            .unwrap()
+        };
+        let request = HttpRequest::post(url("secret"), &[("db", "pubmed"), ("id", "1,2")]);
+        let recorder = RecordingTransport::new(std::sync::Arc::new(EchoTransport), &dir);
+        let recorded = recorder.send(&request).await.unwrap();
+
+        let fixture = std::fs::read_to_string(dir.join(request.fixture_name())).unwrap();
+        assert!(!fixture.contains("secret"));
+
+        // Replays regardless of the API key
+        let replayer = ReplayTransport::new(&dir);
+        let request = HttpRequest::post(url("other"), &[("db", "pubmed"), ("id", "1,2")]);
+        assert_eq!(replayer.send(&request).await.unwrap(), recorded);
+
+        let request = HttpRequest::post(url("other"), &[("db", "pubmed"), ("id", "3")]);
+        assert!(matches!(
+            replayer.send(&request).await,
+            Err(crate::PubmedError::NotFound(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    /// Serves EFetch requests with minimal articles; IDs of 666 fail.
+    #[derive(Debug)]
+    struct EFetchStub;
+
+    impl crate::Transport for EFetchStub {
+        fn send<'a>(&'a self, request: &'a crate::HttpRequest) -> crate::TransportFuture<'a> {
+            Box::pin(async move {
+                let url = reqwest::Url::parse(&request.url).unwrap();
+                let ids: Vec<String> = url
+                    .query_pairs()
+                    .filter(|(k, _)| k == "id")
+                    .flat_map(|(_, v)| v.split(',').map(str::to_string).collect::<Vec<_>>())
+                    .collect();
+                if ids.iter().any(|id| id == "666") {
+                    return Ok(crate::HttpResponse {
+                        status: 400,
+                        retry_after: None,
+                        body: String::new(),
+                    });
+                }
+                let articles: String = ids
+                    .iter()
+                    .map(|id| {
+                        format!(
+                            "<PubmedArticle><MedlineCitation><PMID>{id}</PMID></MedlineCitation></PubmedArticle>"
+                        )
+                    })
+                    .collect();
+                Ok(crate::HttpResponse {
+                    status: 200,
+                    retry_after: None,
+                    body: format!("<PubmedArticleSet>{articles}</PubmedArticleSet>"),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bulk_articles() {
+        use crate::{BulkFetchOptions, ResultOrder};
+        use futures::StreamExt;
+        use std::sync::{Arc, Mutex};
+        let client = crate::Client::builder()
+            .transport(Arc::new(EFetchStub))
+            .rate_limit(1000)
+            .retry_policy(crate::RetryPolicy::none())
+            .build()
+            .unwrap();
+        let ids = [1, 2, 3, 4, 666, 6, 7, 3];
+        let reports = Arc::new(Mutex::new(vec![]));
+        let progress_reports = Arc::clone(&reports);
+        let options = BulkFetchOptions::default()
+            .with_chunk_size(2)
+            .with_concurrency(3)
+            .with_order(ResultOrder::Input)
+            .with_progress(move |p| progress_reports.lock().unwrap().push(*p));
+
+        let chunks: Vec<crate::ChunkResult> = client
+            .articles_stream(&ids, options.clone())
+            .collect()
+            .await;
+        let indices: Vec<usize> = chunks.iter().map(|c| c.index).collect();
+        assert_eq!(indices, vec![0, 1, 2, 3]);
+        assert_eq!(chunks[2].ids, vec![666, 6]);
+        assert!(matches!(
+            chunks[2].result,
+            Err(crate::PubmedError::HttpStatus { status: 400, .. })
+        ));
+        let last = *reports.lock().unwrap().last().unwrap();
+        assert_eq!(last.chunks_done, 4);
+        assert_eq!(last.chunks_total, 4);
+        assert_eq!(last.failed_chunks, 1);
+        assert_eq!(last.articles_done, 5);
+
+        let report = client
+            .bulk_articles(&ids, options.with_order(ResultOrder::Completion))
+            .await;
+        let pmids: Vec<u64> = report
+            .articles
+            .iter()
+            .map(|a| a.medline_citation.as_ref().unwrap().pmid)
+            .collect();
+        assert_eq!(pmids, vec![1, 2, 3, 4, 7]);
+        assert_eq!(report.failures.len(), 1);
+        assert_eq!(report.failures[0].0, vec![666, 6]);
+    }
+
+    #[test]
+    fn test_rich_text_from_xml() {
+        let xml = "<ArticleTitle>Growth of <i>E. coli</i> at 10<sup>-3</sup> M Ca<sup>2+</sup> &amp; <b>H<sub>2</sub>O</b> <b><i>in vivo</i></b>.</ArticleTitle>";
+        let doc = root_element(xml);
+        let title = RichText::new_from_xml(&doc.root_element());
+        assert_eq!(
+            title.plain_text(),
+            "Growth of E. coli at 10-3 M Ca2+ & H2O in vivo."
+        );
+        assert_eq!(title.to_string(), title.plain_text());
+        assert_eq!(title.spans[1].text, "E. coli");
+        assert_eq!(title.spans[1].formats, vec![TextFormat::Italic]);
+        assert_eq!(
+            title.to_html(),
+            "Growth of <i>E. coli</i> at 10<sup>-3</sup> M Ca<sup>2+</sup> &amp; <b>H<sub>2</sub>O</b> <b><i>in vivo</i></b>."
+        );
+        assert_eq!(
+            title.to_markdown(),
+            "Growth of *E. coli* at 10<sup>-3</sup> M Ca<sup>2+</sup> & **H<sub>2</sub>O** ***in vivo***."
+        );
+
+        let doc = root_element("<Citation> <i>Nature</i> 2020. </Citation>");
+        let citation = RichText::new_from_xml(&doc.root_element()).trim();
+        assert_eq!(citation.plain_text(), "Nature 2020.");
+        assert_eq!(citation.spans[0].formats, vec![TextFormat::Italic]);
+    }
+
+    #[test]
+    fn test_author_with_multiple_affiliations() {
+        let xml = r#"<Author ValidYN="Y">
+            <LastName>Doe</LastName>
+            <AffiliationInfo><Affiliation>Department of Medicine, Harvard Medical School, Boston, MA 02115, USA. jane_doe@hms.harvard.edu.</Affiliation></AffiliationInfo>
+            <AffiliationInfo><Affiliation>Wellcome Sanger Institute, Hinxton CB10 1SA, UK.</Affiliation></AffiliationInfo>
+        </Author>"#;
+        let doc = root_element(xml);
+        let author = crate::Author::new_from_xml(&doc.root_element());
+        assert_eq!(author.affiliation_info.len(), 2);
+        assert_eq!(author.affiliations().count(), 2);
+
+        let first = author.affiliation_info[0].components().unwrap();
+        assert_eq!(first.emails, vec!["jane_doe@hms.harvard.edu"]);
+        assert_eq!(first.department.as_deref(), Some("Department of Medicine"));
+        assert_eq!(first.institution.as_deref(), Some("Harvard Medical School"));
+        assert_eq!(first.postal_code.as_deref(), Some("02115"));
+        assert_eq!(first.country.as_deref(), Some("United States"));
+        assert_eq!(first.segments.len(), 5);
+
+        let second = author.affiliation_info[1].components().unwrap();
+        assert!(second.emails.is_empty());
+        assert_eq!(second.department, None);
+        assert_eq!(
+            second.institution.as_deref(),
+            Some("Wellcome Sanger Institute")
+        );
+        assert_eq!(second.postal_code.as_deref(), Some("CB10 1SA"));
+        assert_eq!(second.country.as_deref(), Some("United Kingdom"));
+
+        let parsed = crate::AffiliationComponents::parse(
+            "Institut Pasteur, Unité de Génétique, F-75015 Paris, France. Electronic address: a.b@pasteur.fr.",
+        );
+        assert_eq!(parsed.emails, vec!["a.b@pasteur.fr"]);
+        assert_eq!(parsed.postal_code.as_deref(), Some("F-75015"));
+        assert_eq!(parsed.country.as_deref(), Some("France"));
+        assert_eq!(parsed.institution.as_deref(), Some("Institut Pasteur"));
+        assert_eq!(parsed.segments.last().map(String::as_str), Some("France"));
+    }
+
+    #[test]
+    fn test_pagination_ranges() {
+        let xml = "<Pagination><StartPage>1123</StartPage><EndPage>1129</EndPage><MedlinePgn>1123-9</MedlinePgn></Pagination>";
+        let doc = root_element(xml);
+        let pagination = crate::Pagination::new_from_xml(&doc.root_element());
+        assert_eq!(pagination.start_page.as_deref(), Some("1123"));
+        assert_eq!(pagination.end_page.as_deref(), Some("1129"));
+        assert_eq!(pagination.to_string(), "1123–1129");
+        assert_eq!(pagination.page_count(), Some(7));
+
+        let range = crate::PageRange::parse("1123-9").unwrap();
+        assert_eq!(range.end.as_deref(), Some("1129"));
+        assert_eq!(
+            crate::PageRange::parse("S12-4").unwrap().to_string(),
+            "S12–S14"
+        );
+        assert_eq!(
+            crate::PageRange::parse("e123-e125").unwrap().page_count(),
+            Some(3)
+        );
+        assert!(crate::PageRange::parse("see text").is_none());
+
+        let pgn = |s: &str| crate::Pagination {
+            medline_pgn: Some(s.to_string()),
+            ..Default::default()
+        };
+        let pagination = pgn("321-9, 334-6");
+        assert_eq!(pagination.ranges().len(), 2);
+        assert_eq!(pagination.to_string(), "321–329, 334–336");
+        assert_eq!(pagination.first_page().as_deref(), Some("321"));
+        assert_eq!(pagination.last_page().as_deref(), Some("336"));
+        assert_eq!(pagination.page_count(), Some(12));
+
+        let pagination = pgn("S12-4; discussion S15");
+        assert_eq!(pagination.to_string(), "S12–S14, S15");
+        assert_eq!(pagination.page_count(), Some(4));
+
+        let pagination = pgn("e0123456");
+        assert!(pagination.is_article_number());
+        assert_eq!(pagination.page_count(), None);
+        assert_eq!(pagination.to_string(), "e0123456");
+        assert!(!pgn("123").is_article_number());
+        assert_eq!(pgn("123").page_count(), Some(1));
+    }
+
+    #[test]
+    fn test_medline_date_and_season() {
+        let parse = |xml: &str| {
+            let doc = root_element(xml);
+            PubMedDate::new_from_xml(&doc.root_element())
+        };
+
+        let date =
+            parse("<PubDate><MedlineDate>1998 Dec-1999 Jan</MedlineDate></PubDate>").unwrap();
+        assert_eq!(date.medline_date.as_deref(), Some("1998 Dec-1999 Jan"));
+        assert_eq!((date.year, date.month, date.day), (1998, 12, 0));
+        let range = date.range.unwrap();
+        assert_eq!((range.start_year, range.start_month), (1998, 12));
+        assert_eq!((range.end_year, range.end_month), (1999, 1));
+        assert_eq!(range.precision, 10);
+
+        let date = parse("<PubDate><Year>2001</Year><Season>Spring</Season></PubDate>").unwrap();
+        assert_eq!(date.season.as_deref(), Some("Spring"));
+        assert_eq!(date.precision(), 9);
+        let range = date.range.unwrap();
+        assert_eq!((range.start_year, range.start_month), (2001, 3));
+        assert_eq!((range.end_year, range.end_month), (2001, 5));
+
+        let range = crate::DateRange::parse("1999 Jun 29-Jul 5").unwrap();
+        assert_eq!(
+            (
+                range.start_month,
+                range.start_day,
+                range.end_month,
+                range.end_day
+            ),
+            (6, 29, 7, 5)
+        );
+        assert_eq!(range.precision, 11);
+
+        let range = crate::DateRange::parse("1998 Dec 15-1999 Jan").unwrap();
+        assert_eq!(
+            (range.start_year, range.start_month, range.start_day),
+            (1998, 12, 15)
+        );
+        assert_eq!(
+            (range.end_year, range.end_month, range.end_day),
+            (1999, 1, 0)
+        );
+        assert_eq!(range.precision, 10);
+
+        let range = crate::DateRange::parse("2000 Jan 5-Feb").unwrap();
+        assert_eq!((range.start_month, range.start_day), (1, 5));
+        assert_eq!((range.end_month, range.end_day), (2, 0));
+
+        let range = crate::DateRange::parse("1999 Nov-Dec 3").unwrap();
+        assert_eq!((range.start_month, range.start_day), (11, 0));
+        assert_eq!((range.end_month, range.end_day), (12, 3));
+
+        let range = crate::DateRange::parse("2000 Dec 23-30").unwrap();
+        assert_eq!(
+            (range.start_day, range.end_month, range.end_day),
+            (23, 12, 30)
+        );
+        assert_eq!(range.precision, 11);
+
+        let range = crate::DateRange::parse("1977-1978 Fall-Winter").unwrap();
+        assert_eq!((range.start_year, range.start_month), (1977, 9));
+        assert_eq!((range.end_year, range.end_month), (1978, 2));
+
+        let range = crate::DateRange::parse("2001 Winter").unwrap();
+        assert_eq!((range.start_year, range.end_year), (2001, 2002));
+
+        let range = crate::DateRange::parse("1998-1999").unwrap();
+        assert_eq!(
+            (range.start_year, range.end_year, range.precision),
+            (1998, 1999, 9)
+        );
+
+        assert!(crate::DateRange::parse("Spring").is_none());
+        assert!(parse("<PubDate><MedlineDate>Unknown</MedlineDate></PubDate>").is_none());
+    }
+
+    /// Answers ECitMatch requests in reverse order, with PMID 1000 + row key.
+    #[derive(Debug)]
+    struct ECitMatchStub;
+
+    impl crate::Transport for ECitMatchStub {
+        fn send<'a>(&'a self, request: &'a crate::HttpRequest) -> crate::TransportFuture<'a> {
+            Box::pin(async move {
+                let url = reqwest::Url::parse(&request.url).unwrap();
+                let bdata = url
+                    .query_pairs()
+                    .find(|(k, _)| k == "bdata")
+                    .map(|(_, v)| v.to_string())
+                    .unwrap_or_default();
+                let lines: Vec<String> = bdata
+                    .split('\r')
+                    .rev()
+                    .map(|line| {
+                        let key = line.split('|').nth(5).unwrap_or("");
+                        format!("{line}{}", 1000 + key.parse::<u64>().unwrap_or(0))
+                    })
+                    .collect();
+                Ok(crate::HttpResponse {
+                    status: 200,
+                    retry_after: None,
+                    body: lines.join("\n"),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_citation_match_keys() {
+        let client = crate::Client::builder()
+            .transport(std::sync::Arc::new(ECitMatchStub))
+            .rate_limit(1000)
+            .retry_policy(crate::RetryPolicy::none())
+            .build()
+            .unwrap();
+        let key = |key: &str| crate::CitationKey {
+            journal: Some("science".to_string()),
+            key: key.to_string(),
+            ..Default::default()
+        };
+        let keys = [key(""), key(""), key("a|b"), key("dup"), key("dup")];
+        let matches = client.citation_match(&keys).await.unwrap();
+        let pmids: Vec<Option<u64>> = matches.iter().map(crate::CitationMatch::pmid).collect();
+        assert_eq!(
+            pmids,
+            vec![Some(1000), Some(1001), Some(1002), Some(1003), Some(1004)]
+        );
+        assert_eq!(matches[2].key, "a|b");
+        assert_eq!(matches[4].key, "dup");
+    }
+
+    #[test]
+    fn test_affiliation_country_longest_match() {
+        let country = |s: &str| crate::AffiliationComponents::parse(s).country;
+        let cases = [
+            (
+                "Queen's University, Belfast, Northern Ireland",
+                "United Kingdom",
+            ),
+            (
+                "University of Papua New Guinea, Port Moresby, Papua New Guinea",
+                "Papua New Guinea",
+            ),
+            (
+                "University of Kinshasa, Kinshasa, Democratic Republic of the Congo",
+                "Democratic Republic of the Congo",
+            ),
+            (
+                "University of New Mexico, Albuquerque, New Mexico",
+                "United States",
+            ),
+            ("University of Sydney, Sydney, New South Wales", "Australia"),
+            ("Dalhousie University, Halifax, Nova Scotia", "Canada"),
+            ("Cardiff University, Cardiff, Wales", "United Kingdom"),
+            ("UNAM, Mexico City, Mexico", "Mexico"),
+            ("Trinity College, Dublin, Ireland", "Ireland"),
+            ("Tbilisi State University, Tbilisi, Georgia", "Georgia"),
+        ];
+        for (affiliation, expected) in cases {
+            assert_eq!(
+                country(affiliation).as_deref(),
+                Some(expected),
+                "{affiliation}"
+            );
+        }
+    }
+
+    /// Answers requests with `responses` in turn and counts the requests.
+    #[derive(Debug)]
+    struct SequenceStub {
+        responses: std::sync::Mutex<Vec<crate::HttpResponse>>,
+        requests: std::sync::atomic::AtomicUsize,
+    }
+
+    impl SequenceStub {
+        fn new(responses: &[(u16, Option<&str>, &str)]) -> Self {
+            let responses = responses
+                .iter()
+                .rev()
+                .map(|(status, retry_after, body)| crate::HttpResponse {
+                    status: *status,
+                    retry_after: retry_after.map(str::to_string),
+                    body: body.to_string(),
+                })
+                .collect();
+            Self {
+                responses: std::sync::Mutex::new(responses),
+                requests: std::sync::atomic::AtomicUsize::new(0),
+            }
+        }
+
+        fn requests(&self) -> usize {
+            self.requests.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    impl crate::Transport for SequenceStub {
+        fn send<'a>(&'a self, _request: &'a crate::HttpRequest) -> crate::TransportFuture<'a> {
+            Box::pin(async move {
+                self.requests
+                    .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(self.responses.lock().unwrap().pop().unwrap())
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_retries() {
+        use crate::{PubmedError, RetryPolicy};
+        use std::sync::Arc;
+        use std::time::Duration;
+        const FOUND: &str = r#"{"esearchresult":{"count":"1","idlist":["1"]}}"#;
+        let client = |stub: &Arc<SequenceStub>, max_attempts: u32| {
+            crate::Client::builder()
+                .transport(stub.clone())
+                .rate_limit(1000)
+                .retry_policy(RetryPolicy {
+                    max_attempts,
+                    initial_backoff: Duration::from_millis(20),
+                    max_backoff: Duration::from_secs(1),
+                    jitter: false,
+                })
+                .build()
+                .unwrap()
+        };
+
+        // 503 and 429 are retried, the latter after its Retry-After
+        let stub = Arc::new(SequenceStub::new(&[
+            (503, None, ""),
+            (429, Some("0"), ""),
+            (
+                200,
+                None,
+                r#"{"esearchresult":{"ERROR":"Search Backend failed"}}"#,
+            ),
+            (200, None, FOUND),
+        ]));
+        let ids = client(&stub, 4).article_ids_from_query("x", 10).await;
+        assert_eq!(ids.unwrap(), vec![1]);
+        assert_eq!(stub.requests(), 4);
+
+        // max_attempts ends the loop; the error holds every try
+        let stub = Arc::new(SequenceStub::new(&[
+            (429, Some("0"), ""),
+            (503, None, ""),
+            (503, None, ""),
+            (200, None, FOUND),
+        ]));
+        let error = client(&stub, 3)
+            .article_ids_from_query("x", 10)
+            .await
+            .unwrap_err();
+        assert_eq!(stub.requests(), 3);
+        assert!(matches!(error, PubmedError::HttpStatus { status: 503, .. }));
+        let attempts = error.attempts();
+        assert_eq!(attempts.len(), 3);
+        assert_eq!(attempts[0].status, Some(429));
+        assert_eq!(attempts[0].waited, Some(Duration::ZERO));
+        assert_eq!(attempts[1].status, Some(503));
+        assert_eq!(attempts[1].waited, Some(Duration::from_millis(40)));
+        assert_eq!(attempts[2].waited, None);
+
+        // Permanent NCBI errors fail at once
+        let stub = Arc::new(SequenceStub::new(&[
+            (
+                200,
+                None,
+                r#"{"esearchresult":{"ERROR":"Invalid db name"}}"#,
+            ),
+            (200, None, FOUND),
+        ]));
+        let error = client(&stub, 4)
+            .article_ids_from_query("x", 10)
+            .await
+            .unwrap_err();
+        assert_eq!(stub.requests(), 1);
+        assert!(matches!(&error, PubmedError::Api { message, .. } if message == "Invalid db name"));
+        assert_eq!(error.attempts().len(), 1);
+        assert_eq!(error.attempts()[0].status, Some(200));
+    }
+
+    /// Answers ID Converter requests from a fixed set of records and keeps
+    /// the requested URLs.
+    #[derive(Debug, Default)]
+    struct IdConvStub {
+        urls: std::sync::Mutex<Vec<String>>,
+    }
+
+    impl crate::Transport for IdConvStub {
+        fn send<'a>(&'a self, request: &'a crate::HttpRequest) -> crate::TransportFuture<'a> {
+            Box::pin(async move {
+                const RECORDS: &[(&str, &str, &str)] = &[
+                    ("23193287", "PMC3531190", "10.1093/nar/gks1195"),
+                    ("22722859", "PMC3386567", "10.1038/nature11174"),
+                ];
+                self.urls.lock().unwrap().push(request.url.clone());
+                let url = reqwest::Url::parse(&request.url).unwrap();
+                let param = |name: &str| {
+                    url.query_pairs()
+                        .find(|(k, _)| k == name)
+                        .map(|(_, v)| v.to_string())
+                        .unwrap()
+                };
+                let idtype = param("idtype");
+                let records: Vec<serde_json::Value> = param("ids")
+                    .split(',')
+                    .map(|id| {
+                        let found = RECORDS.iter().find(|(pmid, pmcid, doi)| {
+                            match idtype.as_str() {
+                                "pmid" => id == *pmid,
+                                "pmcid" => id.eq_ignore_ascii_case(pmcid),
+                                _ => id == *doi,
+                            }
+                        });
+                        match found {
+                            Some((pmid, pmcid, doi)) => serde_json::json!({
+                                "requested-id": id, "pmid": pmid, "pmcid": pmcid, "doi": doi
+                            }),
+                            None => serde_json::json!({
+                                "requested-id": id, "status": "error", "errmsg": "invalid article id"
+                            }),
+                        }
+                    })
+                    .collect();
+                Ok(crate::HttpResponse {
+                    status: 200,
+                    retry_after: None,
+                    body: serde_json::json!({"status": "ok", "records": records}).to_string(),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_convert_ids() {
+        use std::sync::Arc;
+        let stub = Arc::new(IdConvStub::default());
+        let client = crate::Client::builder()
+            .transport(stub.clone())
+            .id_converter_url("http://localhost:8080/idconv/")
+            .rate_limit(1000)
+            .retry_policy(crate::RetryPolicy::none())
+            .build()
+            .unwrap();
+        let ids = [
+            "https://doi.org/10.1038/NATURE11174",
+            "23193287",
+            "pmc3386567",
+            "doi:10.1093/nar/gks1195",
+            "99999999",
+            "foo",
+        ];
+        let records = client.convert_ids(&ids).await.unwrap();
+        let requested: Vec<&str> = records.iter().map(|r| r.requested_id.as_str()).collect();
+        assert_eq!(requested, ids);
+        let pmids: Vec<Option<u64>> = records.iter().map(|r| r.pmid).collect();
+        assert_eq!(
+            pmids,
+            [
+                Some(22722859),
+                Some(23193287),
+                Some(22722859),
+                Some(23193287),
+                None,
+                None
+            ]
+        );
+        assert_eq!(records[4].error.as_deref(), Some("invalid article id"));
+        assert_eq!(records[5].error.as_deref(), Some("unrecognized ID format"));
+
+        // One request per ID type, all to the configured URL
+        let urls = stub.urls.lock().unwrap();
+        assert_eq!(urls.len(), 3);
+        assert!(urls
+            .iter()
+            .all(|url| url.starts_with("http://localhost:8080/idconv/?")));
+        assert!(urls
+            .iter()
+            .any(|url| url.contains("ids=10.1038%2Fnature11174%2C10.1093%2Fnar%2Fgks1195")));
+    }
+
+    /// Serves EFetch requests via GET or POST, answering with the articles
+    /// in reverse order, and keeps the method and ID count of each request.
+    #[derive(Debug, Default)]
+    struct EFetchRecorder {
+        requests: std::sync::Mutex<Vec<(crate::HttpMethod, usize)>>,
+    }
+
+    impl crate::Transport for EFetchRecorder {
+        fn send<'a>(&'a self, request: &'a crate::HttpRequest) -> crate::TransportFuture<'a> {
+            Box::pin(async move {
+                let url = reqwest::Url::parse(&request.url).unwrap();
+                let query: Vec<(String, String)> = url.query_pairs().into_owned().collect();
+                let ids: Vec<String> = query
+                    .iter()
+                    .chain(&request.form)
+                    .filter(|(k, _)| k == "id")
+                    .flat_map(|(_, v)| v.split(',').map(str::to_string).collect::<Vec<_>>())
+                    .collect();
+                self.requests
+                    .lock()
+                    .unwrap()
+                    .push((request.method, ids.len()));
+                let articles: String = ids
+                    .iter()
+                    .rev()
+                    .map(|id| {
+                        format!(
+                            "<PubmedArticle><MedlineCitation><PMID>{id}</PMID></MedlineCitation></PubmedArticle>"
+                        )
+                    })
+                    .collect();
+                Ok(crate::HttpResponse {
+                    status: 200,
+                    retry_after: None,
+                    body: format!("<PubmedArticleSet>{articles}</PubmedArticleSet>"),
+                })
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_client_articles_batches() {
+        use crate::HttpMethod::{Get, Post};
+        use std::sync::Arc;
+        let stub = Arc::new(EFetchRecorder::default());
+        let client = crate::Client::builder()
+            .transport(stub.clone())
+            .rate_limit(1000)
+            .retry_policy(crate::RetryPolicy::none())
+            .build()
+            .unwrap();
+        let pmids = |articles: Vec<crate::PubmedArticle>| -> Vec<u64> {
+            articles
+                .iter()
+                .map(|a| a.medline_citation.as_ref().unwrap().pmid)
+                .collect()
+        };
+
+        // Up to EFETCH_POST_THRESHOLD IDs go in a single GET
+        let ids: Vec<u64> = (1..=crate::EFETCH_POST_THRESHOLD as u64).collect();
+        assert_eq!(pmids(client.articles(&ids).await.unwrap()), ids);
+        assert_eq!(
+            stub.requests.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            [(Get, crate::EFETCH_POST_THRESHOLD)]
+        );
+
+        // More are POSTed, in batches of EFETCH_BATCH_SIZE
+        let ids: Vec<u64> = (1..=2_500).map(|i| (i * 7_919) % 100_003).collect();
+        assert_eq!(pmids(client.articles(&ids).await.unwrap()), ids);
+        assert_eq!(
+            stub.requests.lock().unwrap().drain(..).collect::<Vec<_>>(),
+            [
+                (Post, crate::EFETCH_BATCH_SIZE),
+                (Post, crate::EFETCH_BATCH_SIZE),
+                (Post, 500)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_article_cache_concurrent_store() {
+        use crate::ArticleCache;
+        let dir = std::env::temp_dir().join(format!(
+            "pubmed_cache_concurrent_test_{}",
+            std::process::id()
+        ));
+        let cache = ArticleCache::new(&dir);
+        let response = "<PubmedArticleSet><PubmedArticle><MedlineCitation><PMID>4004</PMID>\
+                        </MedlineCitation></PubmedArticle></PubmedArticleSet>";
+        let threads: Vec<_> = (0..8)
+            .map(|_| {
+                let cache = cache.clone();
+                std::thread::spawn(move || {
+                    (0..20).all(|_| cache.store(response).is_ok_and(|stored| stored == 1))
+                })
+            })
+            .collect();
+        assert!(threads.into_iter().all(|t| t.join().unwrap()));
+        assert!(cache.get(4004).is_some());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
 }
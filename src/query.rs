@@ -0,0 +1,381 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// A PubMed search field, rendered as its bracketed tag, e.g. `[au]`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Field {
+    All,
+    Affiliation,
+    Author,
+    FirstAuthor,
+    LastAuthor,
+    Title,
+    TitleAbstract,
+    TextWord,
+    Journal,
+    Language,
+    PublicationType,
+    Pmid,
+    Volume,
+    Issue,
+    Pagination,
+    Grant,
+    Substance,
+    /// Location ID (DOI or publisher ID)
+    LocationId,
+    /// Any field tag not covered above, without brackets, e.g. `"pa"`
+    Other(String),
+}
+
+impl Field {
+    #[must_use]
+    pub fn tag(&self) -> &str {
+        match self {
+            Field::All => "all",
+            Field::Affiliation => "ad",
+            Field::Author => "au",
+            Field::FirstAuthor => "1au",
+            Field::LastAuthor => "lastau",
+            Field::Title => "ti",
+            Field::TitleAbstract => "tiab",
+            Field::TextWord => "tw",
+            Field::Journal => "ta",
+            Field::Language => "la",
+            Field::PublicationType => "pt",
+            Field::Pmid => "pmid",
+            Field::Volume => "vi",
+            Field::Issue => "ip",
+            Field::Pagination => "pg",
+            Field::Grant => "gr",
+            Field::Substance => "nm",
+            Field::LocationId => "lid",
+            Field::Other(tag) => tag,
+        }
+    }
+}
+
+/// The date a [`Query::date_range`] applies to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DateField {
+    Publication,
+    Entrez,
+    Create,
+    Completion,
+    Modification,
+    Mesh,
+}
+
+impl DateField {
+    #[must_use]
+    pub fn tag(&self) -> &str {
+        match self {
+            DateField::Publication => "dp",
+            DateField::Entrez => "edat",
+            DateField::Create => "crdt",
+            DateField::Completion => "dcom",
+            DateField::Modification => "lr",
+            DateField::Mesh => "mhda",
+        }
+    }
+}
+
+/// A PubMed query, rendered to the `term` parameter of ESearch via
+/// `to_string()`, with phrases quoted and sub-queries parenthesized.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Query {
+    Term {
+        text: String,
+        field: Option<Field>,
+    },
+    Mesh {
+        term: String,
+        explode: bool,
+        major_topic: bool,
+    },
+    DateRange {
+        field: DateField,
+        from: String,
+        to: String,
+    },
+    Proximity {
+        terms: String,
+        field: Field,
+        distance: u32,
+    },
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>, Box<Query>),
+    /// Passed through verbatim, for syntax not covered by the builder
+    Raw(String),
+}
+
+impl Query {
+    /// A term searched in all fields.
+    #[must_use]
+    pub fn term(text: &str) -> Self {
+        Query::Term {
+            text: text.to_string(),
+            field: None,
+        }
+    }
+
+    /// A term restricted to one search field.
+    #[must_use]
+    pub fn field(text: &str, field: Field) -> Self {
+        Query::Term {
+            text: text.to_string(),
+            field: Some(field),
+        }
+    }
+
+    /// A MeSH heading, including its narrower terms (`[mh]`).
+    #[must_use]
+    pub fn mesh(term: &str) -> Self {
+        Query::Mesh {
+            term: term.to_string(),
+            explode: true,
+            major_topic: false,
+        }
+    }
+
+    /// A MeSH heading without its narrower terms (`[mh:noexp]`).
+    #[must_use]
+    pub fn mesh_no_explode(term: &str) -> Self {
+        Query::Mesh {
+            term: term.to_string(),
+            explode: false,
+            major_topic: false,
+        }
+    }
+
+    /// A MeSH heading as major topic (`[majr]`).
+    #[must_use]
+    pub fn mesh_major(term: &str) -> Self {
+        Query::Mesh {
+            term: term.to_string(),
+            explode: true,
+            major_topic: true,
+        }
+    }
+
+    /// A date range such as `2020/01/01:2020/06/30[dp]`. Dates are
+    /// `YYYY`, `YYYY/MM` or `YYYY/MM/DD`; use `"3000"` for an open end.
+    #[must_use]
+    pub fn date_range(field: DateField, from: &str, to: &str) -> Self {
+        Query::DateRange {
+            field,
+            from: from.to_string(),
+            to: to.to_string(),
+        }
+    }
+
+    /// Terms that must occur within `distance` words of each other, e.g.
+    /// `"hip pain"[tiab:~2]`. PubMed supports this for title, title/abstract
+    /// and affiliation.
+    #[must_use]
+    pub fn proximity(terms: &str, field: Field, distance: u32) -> Self {
+        Query::Proximity {
+            terms: terms.to_string(),
+            field,
+            distance,
+        }
+    }
+
+    #[must_use]
+    pub fn raw(query: &str) -> Self {
+        Query::Raw(query.to_string())
+    }
+
+    #[must_use]
+    pub fn and(self, other: Query) -> Self {
+        match self {
+            Query::And(mut parts) => {
+                parts.push(other);
+                Query::And(parts)
+            }
+            q => Query::And(vec![q, other]),
+        }
+    }
+
+    #[must_use]
+    pub fn or(self, other: Query) -> Self {
+        match self {
+            Query::Or(mut parts) => {
+                parts.push(other);
+                Query::Or(parts)
+            }
+            q => Query::Or(vec![q, other]),
+        }
+    }
+
+    #[must_use]
+    pub fn not(self, other: Query) -> Self {
+        Query::Not(Box::new(self), Box::new(other))
+    }
+
+    fn is_compound(&self) -> bool {
+        matches!(
+            self,
+            Query::And(_) | Query::Or(_) | Query::Not(_, _) | Query::Raw(_)
+        )
+    }
+
+    /// Quotes `text` as a phrase if it contains whitespace, query syntax or
+    /// a boolean operator. PubMed has no escape for `"`, so those are dropped.
+    fn quote(text: &str) -> String {
+        let text = text.replace('"', "").trim().to_string();
+        let needs_quotes = text.chars().any(|c| {
+            c.is_whitespace()
+                || matches!(c, '(' | ')' | '[' | ']' | ':' | '&' | '|' | ',' | '#' | '/')
+        }) || matches!(text.to_uppercase().as_str(), "AND" | "OR" | "NOT");
+        if needs_quotes {
+            format!("\"{text}\"")
+        } else {
+            text
+        }
+    }
+
+    fn fmt_child(f: &mut fmt::Formatter<'_>, child: &Query) -> fmt::Result {
+        if child.is_compound() {
+            write!(f, "({child})")
+        } else {
+            write!(f, "{child}")
+        }
+    }
+
+    fn fmt_joined(f: &mut fmt::Formatter<'_>, parts: &[Query], op: &str) -> fmt::Result {
+        for (i, part) in parts.iter().enumerate() {
+            if i > 0 {
+                write!(f, " {op} ")?;
+            }
+            Self::fmt_child(f, part)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Query::Term { text, field: None } => write!(f, "{}", Self::quote(text)),
+            Query::Term {
+                text,
+                field: Some(field),
+            } => write!(f, "{}[{}]", Self::quote(text), field.tag()),
+            Query::Mesh {
+                term,
+                explode,
+                major_topic,
+            } => {
+                let tag = if *major_topic { "majr" } else { "mh" };
+                let noexp = if *explode { "" } else { ":noexp" };
+                write!(f, "\"{}\"[{tag}{noexp}]", term.replace('"', "").trim())
+            }
+            Query::DateRange { field, from, to } => {
+                write!(f, "{}:{}[{}]", from.trim(), to.trim(), field.tag())
+            }
+            Query::Proximity {
+                terms,
+                field,
+                distance,
+            } => write!(
+                f,
+                "\"{}\"[{}:~{distance}]",
+                terms.replace('"', "").trim(),
+                field.tag()
+            ),
+            Query::And(parts) => Self::fmt_joined(f, parts, "AND"),
+            Query::Or(parts) => Self::fmt_joined(f, parts, "OR"),
+            Query::Not(a, b) => {
+                Self::fmt_child(f, a)?;
+                write!(f, " NOT ")?;
+                Self::fmt_child(f, b)
+            }
+            Query::Raw(raw) => write!(f, "{raw}"),
+        }
+    }
+}
+
+/// ESearch result ordering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SortOrder {
+    Relevance,
+    /// Newest first
+    PublicationDate,
+    /// By first author, ascending
+    Author,
+    /// By journal name, ascending
+    JournalName,
+}
+
+impl SortOrder {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            SortOrder::Relevance => "relevance",
+            SortOrder::PublicationDate => "pub_date",
+            SortOrder::Author => "Author",
+            SortOrder::JournalName => "JournalName",
+        }
+    }
+}
+
+/// The date ESearch's `mindate`/`maxdate`/`reldate` filters apply to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SearchDateType {
+    Publication,
+    Entrez,
+    Modification,
+}
+
+impl SearchDateType {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            SearchDateType::Publication => "pdat",
+            SearchDateType::Entrez => "edat",
+            SearchDateType::Modification => "mdat",
+        }
+    }
+}
+
+/// Optional ESearch parameters. Dates are `YYYY`, `YYYY/MM` or `YYYY/MM/DD`;
+/// ESearch needs both `min_date` and `max_date`, so a missing bound is filled
+/// in as `1800` or `3000`. `rel_date` limits results to the last N days.
+///
+/// For example, everything entered in the last 7 days, newest first:
+/// `SearchOptions { date_type: Some(SearchDateType::Entrez), rel_date: Some(7),
+/// sort: Some(SortOrder::PublicationDate), ..Default::default() }`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SearchOptions {
+    pub sort: Option<SortOrder>,
+    pub date_type: Option<SearchDateType>,
+    pub min_date: Option<String>,
+    pub max_date: Option<String>,
+    pub rel_date: Option<u32>,
+    /// Retry with ESpell's suggestion if the query finds nothing
+    pub spelling_fallback: bool,
+}
+
+impl SearchOptions {
+    /// The ESearch URL parameters for these options.
+    pub(crate) fn to_params(&self) -> Vec<(&'static str, String)> {
+        let mut ret = vec![];
+        if let Some(sort) = &self.sort {
+            ret.push(("sort", sort.as_str().to_string()));
+        }
+        if let Some(date_type) = &self.date_type {
+            ret.push(("datetype", date_type.as_str().to_string()));
+        }
+        if self.min_date.is_some() || self.max_date.is_some() {
+            let min_date = self.min_date.as_deref().unwrap_or("1800");
+            let max_date = self.max_date.as_deref().unwrap_or("3000");
+            ret.push(("mindate", min_date.trim().to_string()));
+            ret.push(("maxdate", max_date.trim().to_string()));
+        }
+        if let Some(rel_date) = self.rel_date {
+            ret.push(("reldate", rel_date.to_string()));
+        }
+        ret
+    }
+}
@@ -0,0 +1,79 @@
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// NCBI's request budget without an API key.
+pub const DEFAULT_REQUESTS_PER_SECOND: u32 = 3;
+
+/// NCBI's request budget with an API key.
+pub const API_KEY_REQUESTS_PER_SECOND: u32 = 10;
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    last_refill: Instant,
+}
+
+/// A token bucket shared by all clones, so concurrent tasks using copies of
+/// one [`crate::Client`] draw from the same budget. The bucket holds a single
+/// token, which spaces requests evenly and keeps any one-second window
+/// within `requests_per_second`.
+#[derive(Debug, Clone)]
+pub struct RateLimiter {
+    requests_per_second: u32,
+    bucket: Arc<Mutex<Bucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `requests_per_second` requests (at least 1).
+    #[must_use]
+    pub fn new(requests_per_second: u32) -> Self {
+        Self {
+            requests_per_second: requests_per_second.max(1),
+            bucket: Arc::new(Mutex::new(Bucket {
+                tokens: 1.0,
+                last_refill: Instant::now(),
+            })),
+        }
+    }
+
+    #[must_use]
+    pub fn requests_per_second(&self) -> u32 {
+        self.requests_per_second
+    }
+
+    /// Waits until a request may be sent. Each caller reserves its slot
+    /// before sleeping, so waiting tasks are served in call order.
+    pub async fn acquire(&self) {
+        let wait = self.reserve();
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    // Takes a token, letting the balance go negative, and returns how long
+    // the caller has to wait for it
+    fn reserve(&self) -> Duration {
+        let rate = f64::from(self.requests_per_second);
+        let mut bucket = self
+            .bucket
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        let now = Instant::now();
+        let elapsed = now.duration_since(bucket.last_refill).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(1.0);
+        bucket.last_refill = now;
+        bucket.tokens -= 1.0;
+        if bucket.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-bucket.tokens / rate)
+        }
+    }
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self::new(DEFAULT_REQUESTS_PER_SECOND)
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// NCBI error messages for conditions that pass by themselves. Other error
+/// payloads, like "Invalid db name", fail at once.
+const TRANSIENT_API_ERRORS: &[&str] = &[
+    "backend failed",
+    "rate limit exceeded",
+    "temporarily unavailable",
+    "timed out",
+    "timeout",
+];
+
+/// When and how often [`crate::Client`] retries a failed request. Retries
+/// happen on HTTP 429 and 5xx, on connection errors and timeouts, on empty
+/// responses, and on `200 OK` responses whose NCBI error payload reports a
+/// temporary problem, such as "Search Backend failed".
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RetryPolicy {
+    /// Total number of tries, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled for each further retry
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// Randomizes each delay to between half and all of its nominal value,
+    /// so concurrent clients do not retry in lockstep
+    pub jitter: bool,
+}
+
+impl RetryPolicy {
+    /// A policy that never retries.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// The delay before retry number `retry` (starting at 1). A server's
+    /// `Retry-After` takes precedence over the computed backoff.
+    #[must_use]
+    pub fn delay(&self, retry: u32, retry_after: Option<Duration>) -> Duration {
+        if let Some(retry_after) = retry_after {
+            return retry_after;
+        }
+        let factor = 2_u32.saturating_pow(retry.saturating_sub(1));
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff);
+        if self.jitter {
+            backoff.mul_f64(0.5 + rand::random::<f64>() / 2.0)
+        } else {
+            backoff
+        }
+    }
+
+    /// Returns why a response should be retried, or `None` if it should not.
+    pub(crate) fn retry_reason(status: u16, body: &str) -> Option<String> {
+        if status == 429 || (500..600).contains(&status) {
+            return Some(format!("HTTP {status}"));
+        }
+        if status != 200 {
+            return None;
+        }
+        let body = body.trim();
+        if body.is_empty() {
+            return Some("empty response".to_string());
+        }
+        if let Some(message) = Self::api_error(body) {
+            let lower = message.to_lowercase();
+            return TRANSIENT_API_ERRORS
+                .iter()
+                .any(|e| lower.contains(e))
+                .then_some(message);
+        }
+        if body.starts_with('{') {
+            let json: serde_json::Value = serde_json::from_str(body).ok()?;
+            if json
+                .get("esearchresult")
+                .is_some_and(|result| result.get("count").is_none())
+            {
+                return Some("empty eSearchResult".to_string());
+            }
+        } else if body.contains("<eSearchResult") && !body.contains("<Count>") {
+            return Some("empty eSearchResult".to_string());
+        }
+        None
+    }
+
+    /// The error message NCBI put in a response body instead of data, if
+    /// any.
+    pub(crate) fn api_error(body: &str) -> Option<String> {
+        let body = body.trim();
+        if let Some(start) = body.find("<ERROR>") {
+            let message = &body[start + 7..];
+            let message = message.split("</ERROR>").next().unwrap_or(message);
+            return Some(message.trim().to_string());
+        }
+        if !body.starts_with('{') {
+            return None;
+        }
+        let json: serde_json::Value = serde_json::from_str(body).ok()?;
+        json["error"]
+            .as_str()
+            .or_else(|| json["esearchresult"]["ERROR"].as_str())
+            .map(str::to_string)
+    }
+
+    /// Parses a `Retry-After` header given in seconds. HTTP dates are not
+    /// supported and fall back to the computed backoff.
+    pub(crate) fn parse_retry_after(value: &str) -> Option<Duration> {
+        value.trim().parse::<u64>().ok().map(Duration::from_secs)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            jitter: true,
+        }
+    }
+}
+
+/// One failed try of a request.
+#[derive(Debug, Clone)]
+pub struct Attempt {
+    pub status: Option<u16>,
+    pub reason: String,
+    /// How long the client waited before the next try, if there was one
+    pub waited: Option<Duration>,
+}
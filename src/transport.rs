@@ -0,0 +1,270 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::error::PubmedError;
+
+/// Query and form parameters that do not identify a request and are left
+/// out of fixture names, so fixtures replay regardless of credentials.
+const UNKEYED_PARAMS: &[&str] = &["api_key", "tool", "email"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HttpMethod {
+    Get,
+    Post,
+}
+
+/// An HTTP request as sent by [`crate::Client`]. POST requests carry their
+/// parameters as a URL-encoded form.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpRequest {
+    pub method: HttpMethod,
+    pub url: String,
+    pub form: Vec<(String, String)>,
+}
+
+impl HttpRequest {
+    #[must_use]
+    pub fn get(url: reqwest::Url) -> Self {
+        Self {
+            method: HttpMethod::Get,
+            url: url.to_string(),
+            form: vec![],
+        }
+    }
+
+    #[must_use]
+    pub fn post<K: AsRef<str>, V: AsRef<str>>(url: reqwest::Url, form: &[(K, V)]) -> Self {
+        Self {
+            method: HttpMethod::Post,
+            url: url.to_string(),
+            form: form
+                .iter()
+                .map(|(k, v)| (k.as_ref().to_string(), v.as_ref().to_string()))
+                .collect(),
+        }
+    }
+
+    /// A file name identifying this request, e.g.
+    /// `esearch.fcgi_5f1c0e8d2a9b7c41.json`. The API key, `tool` and `email`
+    /// do not affect the name.
+    #[must_use]
+    pub fn fixture_name(&self) -> String {
+        let (endpoint, key) = match reqwest::Url::parse(&self.url) {
+            Ok(url) => {
+                let endpoint = url
+                    .path_segments()
+                    .and_then(|mut s| s.next_back())
+                    .filter(|s| !s.is_empty())
+                    .unwrap_or("request")
+                    .to_string();
+                let query: Vec<String> = url
+                    .query_pairs()
+                    .filter(|(k, _)| !UNKEYED_PARAMS.contains(&k.as_ref()))
+                    .map(|(k, v)| format!("{k}={v}"))
+                    .collect();
+                let key = format!(
+                    "{}{}?{}",
+                    url.host_str().unwrap_or(""),
+                    url.path(),
+                    query.join("&")
+                );
+                (endpoint, key)
+            }
+            Err(_) => ("request".to_string(), self.url.clone()),
+        };
+        let form: Vec<String> = self
+            .form
+            .iter()
+            .filter(|(k, _)| !UNKEYED_PARAMS.contains(&k.as_str()))
+            .map(|(k, v)| format!("{k}={v}"))
+            .collect();
+        let key = format!("{:?} {key} {}", self.method, form.join("&"));
+        let endpoint: String = endpoint
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '.' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect();
+        format!("{endpoint}_{:016x}.json", Self::fnv1a(&key))
+    }
+
+    /// The URL with the API key removed, for storing in fixtures.
+    fn redacted_url(&self) -> String {
+        let Ok(mut url) = reqwest::Url::parse(&self.url) else {
+            return self.url.clone();
+        };
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .filter(|(k, _)| k != "api_key")
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        if pairs.is_empty() {
+            url.set_query(None);
+        } else {
+            url.query_pairs_mut().clear().extend_pairs(pairs);
+        }
+        url.to_string()
+    }
+
+    // Stable across Rust versions and platforms, unlike `DefaultHasher`
+    fn fnv1a(s: &str) -> u64 {
+        s.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HttpResponse {
+    pub status: u16,
+    /// The `Retry-After` header, if any
+    pub retry_after: Option<String>,
+    pub body: String,
+}
+
+pub type TransportFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<HttpResponse, PubmedError>> + Send + 'a>>;
+
+/// Sends the HTTP requests of a [`crate::Client`]. Rate limiting and retries
+/// happen in the client, so implementations only need to deliver a single
+/// request. Errors other than [`PubmedError::Transport`] are not retried.
+pub trait Transport: fmt::Debug + Send + Sync {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> TransportFuture<'a>;
+}
+
+/// The default transport, using `reqwest`.
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    http: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    #[must_use]
+    pub fn new(http: reqwest::Client) -> Self {
+        Self { http }
+    }
+}
+
+impl Transport for ReqwestTransport {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let builder = match request.method {
+                HttpMethod::Get => self.http.get(&request.url),
+                HttpMethod::Post => self.http.post(&request.url).form(&request.form),
+            };
+            let response = builder.send().await?;
+            let status = response.status().as_u16();
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .map(std::string::ToString::to_string);
+            let body = response.text().await?;
+            Ok(HttpResponse {
+                status,
+                retry_after,
+                body,
+            })
+        })
+    }
+}
+
+/// A request and its response, as stored in a fixture file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Fixture {
+    request: HttpRequest,
+    response: HttpResponse,
+}
+
+/// Passes requests on to another transport and saves each request/response
+/// pair as a JSON fixture in `dir`, to be served by [`ReplayTransport`].
+/// API keys are not written to the fixtures.
+#[derive(Debug, Clone)]
+pub struct RecordingTransport {
+    inner: Arc<dyn Transport>,
+    dir: PathBuf,
+}
+
+impl RecordingTransport {
+    #[must_use]
+    pub fn new(inner: Arc<dyn Transport>, dir: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            dir: dir.into(),
+        }
+    }
+
+    fn save(&self, request: &HttpRequest, response: &HttpResponse) -> std::io::Result<()> {
+        let mut request = request.clone();
+        request.url = request.redacted_url();
+        request.form.retain(|(k, _)| k != "api_key");
+        let fixture = Fixture {
+            request,
+            response: response.clone(),
+        };
+        fs::create_dir_all(&self.dir)?;
+        let json = serde_json::to_string_pretty(&fixture)?;
+        fs::write(self.dir.join(fixture.request.fixture_name()), json)
+    }
+}
+
+impl Transport for RecordingTransport {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> TransportFuture<'a> {
+        Box::pin(async move {
+            let response = self.inner.send(request).await?;
+            if let Err(e) = self.save(request, &response) {
+                eprintln!("PubMed: could not save fixture: {e}");
+            }
+            Ok(response)
+        })
+    }
+}
+
+/// Serves responses from fixtures saved by [`RecordingTransport`], without
+/// any network access. Requests without a fixture fail with
+/// [`PubmedError::NotFound`].
+#[derive(Debug, Clone)]
+pub struct ReplayTransport {
+    dir: PathBuf,
+}
+
+impl ReplayTransport {
+    #[must_use]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    #[must_use]
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn load(&self, request: &HttpRequest) -> Result<HttpResponse, PubmedError> {
+        let path = self.dir.join(request.fixture_name());
+        let text = fs::read_to_string(&path).map_err(|_| {
+            PubmedError::NotFound(format!(
+                "fixture {} for {:?} {}",
+                path.display(),
+                request.method,
+                request.url
+            ))
+        })?;
+        let fixture: Fixture = serde_json::from_str(&text)?;
+        Ok(fixture.response)
+    }
+}
+
+impl Transport for ReplayTransport {
+    fn send<'a>(&'a self, request: &'a HttpRequest) -> TransportFuture<'a> {
+        Box::pin(async move { self.load(request) })
+    }
+}
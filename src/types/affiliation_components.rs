@@ -0,0 +1,532 @@
+use serde::{Deserialize, Serialize};
+
+/// Country names as used in PubMed affiliations.
+const COUNTRIES: &[&str] = &[
+    "Afghanistan",
+    "Albania",
+    "Algeria",
+    "Andorra",
+    "Angola",
+    "Argentina",
+    "Armenia",
+    "Australia",
+    "Austria",
+    "Azerbaijan",
+    "Bahamas",
+    "Bahrain",
+    "Bangladesh",
+    "Barbados",
+    "Belarus",
+    "Belgium",
+    "Belize",
+    "Benin",
+    "Bhutan",
+    "Bolivia",
+    "Bosnia and Herzegovina",
+    "Botswana",
+    "Brazil",
+    "Brunei",
+    "Bulgaria",
+    "Burkina Faso",
+    "Burundi",
+    "Cambodia",
+    "Cameroon",
+    "Canada",
+    "Cape Verde",
+    "Central African Republic",
+    "Chad",
+    "Chile",
+    "China",
+    "Colombia",
+    "Congo",
+    "Costa Rica",
+    "Cote d'Ivoire",
+    "Croatia",
+    "Cuba",
+    "Cyprus",
+    "Czech Republic",
+    "Democratic Republic of the Congo",
+    "Denmark",
+    "Djibouti",
+    "Dominican Republic",
+    "Ecuador",
+    "Egypt",
+    "El Salvador",
+    "Eritrea",
+    "Estonia",
+    "Eswatini",
+    "Ethiopia",
+    "Fiji",
+    "Finland",
+    "France",
+    "Gabon",
+    "Gambia",
+    "Georgia",
+    "Germany",
+    "Ghana",
+    "Greece",
+    "Grenada",
+    "Guatemala",
+    "Guinea",
+    "Guinea-Bissau",
+    "Guyana",
+    "Haiti",
+    "Honduras",
+    "Hong Kong",
+    "Hungary",
+    "Iceland",
+    "India",
+    "Indonesia",
+    "Iran",
+    "Iraq",
+    "Ireland",
+    "Israel",
+    "Italy",
+    "Jamaica",
+    "Japan",
+    "Jordan",
+    "Kazakhstan",
+    "Kenya",
+    "Kosovo",
+    "Kuwait",
+    "Kyrgyzstan",
+    "Laos",
+    "Latvia",
+    "Lebanon",
+    "Lesotho",
+    "Liberia",
+    "Libya",
+    "Liechtenstein",
+    "Lithuania",
+    "Luxembourg",
+    "Macau",
+    "Madagascar",
+    "Malawi",
+    "Malaysia",
+    "Maldives",
+    "Mali",
+    "Malta",
+    "Mauritania",
+    "Mauritius",
+    "Mexico",
+    "Moldova",
+    "Monaco",
+    "Mongolia",
+    "Montenegro",
+    "Morocco",
+    "Mozambique",
+    "Myanmar",
+    "Namibia",
+    "Nepal",
+    "Netherlands",
+    "New Zealand",
+    "Nicaragua",
+    "Niger",
+    "Nigeria",
+    "North Korea",
+    "North Macedonia",
+    "Norway",
+    "Oman",
+    "Pakistan",
+    "Palestine",
+    "Panama",
+    "Papua New Guinea",
+    "Paraguay",
+    "Peru",
+    "Philippines",
+    "Poland",
+    "Portugal",
+    "Puerto Rico",
+    "Qatar",
+    "Romania",
+    "Russia",
+    "Rwanda",
+    "Saudi Arabia",
+    "Senegal",
+    "Serbia",
+    "Seychelles",
+    "Sierra Leone",
+    "Singapore",
+    "Slovakia",
+    "Slovenia",
+    "Somalia",
+    "South Africa",
+    "South Korea",
+    "South Sudan",
+    "Spain",
+    "Sri Lanka",
+    "Sudan",
+    "Suriname",
+    "Sweden",
+    "Switzerland",
+    "Syria",
+    "Taiwan",
+    "Tajikistan",
+    "Tanzania",
+    "Thailand",
+    "Togo",
+    "Trinidad and Tobago",
+    "Tunisia",
+    "Turkey",
+    "Turkmenistan",
+    "Uganda",
+    "Ukraine",
+    "United Arab Emirates",
+    "United Kingdom",
+    "United States",
+    "Uruguay",
+    "Uzbekistan",
+    "Venezuela",
+    "Vietnam",
+    "Yemen",
+    "Zambia",
+    "Zimbabwe",
+];
+
+/// Other spellings, mapped to the name in [`COUNTRIES`].
+const COUNTRY_ALIASES: &[(&str, &str)] = &[
+    ("USA", "United States"),
+    ("U.S.A", "United States"),
+    ("US", "United States"),
+    ("United States of America", "United States"),
+    ("UK", "United Kingdom"),
+    ("U.K", "United Kingdom"),
+    ("England", "United Kingdom"),
+    ("Scotland", "United Kingdom"),
+    ("Wales", "United Kingdom"),
+    ("Northern Ireland", "United Kingdom"),
+    ("Great Britain", "United Kingdom"),
+    ("P.R. China", "China"),
+    ("PR China", "China"),
+    ("People's Republic of China", "China"),
+    ("Korea", "South Korea"),
+    ("Republic of Korea", "South Korea"),
+    ("The Netherlands", "Netherlands"),
+    ("Holland", "Netherlands"),
+    ("Russian Federation", "Russia"),
+    ("Türkiye", "Turkey"),
+    ("Turkiye", "Turkey"),
+    ("Viet Nam", "Vietnam"),
+    ("Czechia", "Czech Republic"),
+    ("Brasil", "Brazil"),
+    ("Deutschland", "Germany"),
+    ("España", "Spain"),
+    ("Italia", "Italy"),
+    ("UAE", "United Arab Emirates"),
+    ("Côte d'Ivoire", "Cote d'Ivoire"),
+    ("Ivory Coast", "Cote d'Ivoire"),
+    ("Swaziland", "Eswatini"),
+    ("Macedonia", "North Macedonia"),
+    ("Burma", "Myanmar"),
+];
+
+/// US states, so that e.g. "New Mexico" is not read as Mexico. Georgia is
+/// left out and taken to be the country.
+const US_STATES: &[&str] = &[
+    "Alabama",
+    "Alaska",
+    "Arizona",
+    "Arkansas",
+    "California",
+    "Colorado",
+    "Connecticut",
+    "Delaware",
+    "Florida",
+    "Hawaii",
+    "Idaho",
+    "Illinois",
+    "Indiana",
+    "Iowa",
+    "Kansas",
+    "Kentucky",
+    "Louisiana",
+    "Maine",
+    "Maryland",
+    "Massachusetts",
+    "Michigan",
+    "Minnesota",
+    "Mississippi",
+    "Missouri",
+    "Montana",
+    "Nebraska",
+    "Nevada",
+    "New Hampshire",
+    "New Jersey",
+    "New Mexico",
+    "New York",
+    "North Carolina",
+    "North Dakota",
+    "Ohio",
+    "Oklahoma",
+    "Oregon",
+    "Pennsylvania",
+    "Rhode Island",
+    "South Carolina",
+    "South Dakota",
+    "Tennessee",
+    "Texas",
+    "Utah",
+    "Vermont",
+    "Virginia",
+    "Washington",
+    "West Virginia",
+    "Wisconsin",
+    "Wyoming",
+];
+
+/// Australian states and Canadian provinces, so that e.g. "New South Wales"
+/// is not read as Wales.
+const STATES_AND_PROVINCES: &[(&str, &str)] = &[
+    ("Australian Capital Territory", "Australia"),
+    ("New South Wales", "Australia"),
+    ("Northern Territory", "Australia"),
+    ("Queensland", "Australia"),
+    ("South Australia", "Australia"),
+    ("Tasmania", "Australia"),
+    ("Victoria", "Australia"),
+    ("Western Australia", "Australia"),
+    ("Alberta", "Canada"),
+    ("British Columbia", "Canada"),
+    ("Manitoba", "Canada"),
+    ("New Brunswick", "Canada"),
+    ("Newfoundland and Labrador", "Canada"),
+    ("Northwest Territories", "Canada"),
+    ("Nova Scotia", "Canada"),
+    ("Nunavut", "Canada"),
+    ("Ontario", "Canada"),
+    ("Prince Edward Island", "Canada"),
+    ("Quebec", "Canada"),
+    ("Québec", "Canada"),
+    ("Saskatchewan", "Canada"),
+    ("Yukon", "Canada"),
+];
+
+/// Words marking a segment as a department or similar sub-unit.
+const DEPARTMENT_WORDS: &[&str] = &[
+    "department",
+    "dept",
+    "division",
+    "section",
+    "unit",
+    "laboratory",
+    "lab",
+    "faculty",
+    "program",
+    "programme",
+    "group",
+    "service",
+    "chair",
+    "discipline",
+];
+
+/// Words marking a segment as an institution.
+const INSTITUTION_WORDS: &[&str] = &[
+    "university",
+    "universidad",
+    "universidade",
+    "université",
+    "universität",
+    "universiteit",
+    "universitá",
+    "università",
+    "institute",
+    "institut",
+    "instituto",
+    "istituto",
+    "hospital",
+    "hôpital",
+    "ospedale",
+    "klinikum",
+    "college",
+    "center",
+    "centre",
+    "clinic",
+    "foundation",
+    "academy",
+    "ministry",
+    "agency",
+    "council",
+    "inc",
+    "ltd",
+    "llc",
+    "gmbh",
+    "corporation",
+    "company",
+    "organization",
+    "organisation",
+    "infirmary",
+    "school",
+];
+
+/// Best-effort components of a free-text `Affiliation`, see
+/// [`crate::AffiliationInfo::components`]. Affiliation strings follow no
+/// fixed format, so any field may be missing or, occasionally, wrong.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AffiliationComponents {
+    /// Comma-separated parts of the affiliation, without email addresses
+    pub segments: Vec<String>,
+    pub department: Option<String>,
+    pub institution: Option<String>,
+    pub postal_code: Option<String>,
+    /// The English short name, e.g. "United States" for "USA"
+    pub country: Option<String>,
+    pub emails: Vec<String>,
+}
+
+impl AffiliationComponents {
+    #[must_use]
+    pub fn parse(affiliation: &str) -> Self {
+        let mut ret = Self::default();
+        let mut text = String::with_capacity(affiliation.len());
+        for word in affiliation.split_whitespace() {
+            match Self::email(word) {
+                Some(email) => ret.emails.push(email),
+                None => {
+                    text.push_str(word);
+                    text.push(' ');
+                }
+            }
+        }
+        for marker in [
+            "Electronic address:",
+            "Email:",
+            "E-mail:",
+            "email:",
+            "e-mail:",
+        ] {
+            text = text.replace(marker, "");
+        }
+        ret.segments = text
+            .split([',', ';'])
+            .map(|s| s.trim().trim_end_matches('.').trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+
+        if let Some(last) = ret.segments.last() {
+            ret.country = Self::country(last);
+        }
+        ret.postal_code = ret
+            .segments
+            .iter()
+            .rev()
+            .find_map(|segment| Self::postal_code(segment));
+        ret.department = ret
+            .segments
+            .iter()
+            .find(|s| Self::is_department(s))
+            .cloned();
+        ret.institution = ret
+            .segments
+            .iter()
+            .filter(|s| Some(*s) != ret.department.as_ref())
+            .find(|s| Self::has_word(s, INSTITUTION_WORDS))
+            .cloned();
+        ret
+    }
+
+    // A word like "jane.doe@example.org." with surrounding punctuation
+    fn email(word: &str) -> Option<String> {
+        let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+        let (local, domain) = word.split_once('@')?;
+        let valid = !local.is_empty()
+            && domain.contains('.')
+            && !domain.starts_with('.')
+            && !domain.ends_with('.')
+            && !domain.contains('@');
+        valid.then(|| word.to_string())
+    }
+
+    // The country a segment ends with, e.g. "Boston, MA 02115 USA". The
+    // longest matching name wins, so "Papua New Guinea" is not Guinea;
+    // on a tie a country name wins over an alias, state or province.
+    fn country(segment: &str) -> Option<String> {
+        let ends_with = |name: &str| {
+            // Short upper-case aliases like "US" must match exactly
+            if name.len() <= 3 {
+                return segment == name || segment.ends_with(&format!(" {name}"));
+            }
+            segment.eq_ignore_ascii_case(name)
+                || segment
+                    .len()
+                    .checked_sub(name.len() + 1)
+                    .and_then(|i| segment.get(i..))
+                    .is_some_and(|tail| {
+                        tail.starts_with(' ') && tail[1..].eq_ignore_ascii_case(name)
+                    })
+        };
+        COUNTRIES
+            .iter()
+            .map(|name| (*name, *name))
+            .chain(COUNTRY_ALIASES.iter().copied())
+            .chain(US_STATES.iter().map(|state| (*state, "United States")))
+            .chain(STATES_AND_PROVINCES.iter().copied())
+            .filter(|(name, _)| ends_with(name))
+            .min_by_key(|(name, _)| std::cmp::Reverse(name.len()))
+            .map(|(_, country)| country.to_string())
+    }
+
+    // Postal codes like "02115", "02115-1234", "D-69120", "SW1A 1AA" or "H3A 0G4"
+    fn postal_code(segment: &str) -> Option<String> {
+        let words: Vec<&str> = segment.split_whitespace().collect();
+        for (i, word) in words.iter().enumerate().rev() {
+            if let Some(next) = words.get(i + 1) {
+                if Self::is_split_postal_code(word, next) {
+                    return Some(format!("{word} {next}"));
+                }
+            }
+            if Self::is_postal_code(word) {
+                return Some((*word).to_string());
+            }
+        }
+        None
+    }
+
+    fn is_postal_code(word: &str) -> bool {
+        let digits = |s: &str| s.chars().all(|c| c.is_ascii_digit());
+        let numeric = |s: &str| (4..=6).contains(&s.len()) && digits(s);
+        if numeric(word) {
+            return true;
+        }
+        if let Some((prefix, rest)) = word.split_once('-') {
+            // "02115-1234", "D-69120", "CH-1211", "100-0001"
+            return (numeric(prefix) && rest.len() == 4 && digits(rest))
+                || ((1..=3).contains(&prefix.len())
+                    && prefix.chars().all(|c| c.is_ascii_uppercase())
+                    && numeric(rest))
+                || (prefix.len() == 3 && digits(prefix) && rest.len() == 4 && digits(rest));
+        }
+        false
+    }
+
+    // Two-part codes as used in the UK ("SW1A 1AA") and Canada ("H3A 0G4")
+    fn is_split_postal_code(outward: &str, inward: &str) -> bool {
+        let alnum_upper = |s: &str| {
+            s.chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        };
+        let has_digit = |s: &str| s.chars().any(|c| c.is_ascii_digit());
+        (2..=4).contains(&outward.len())
+            && inward.len() == 3
+            && alnum_upper(outward)
+            && alnum_upper(inward)
+            && has_digit(outward)
+            && inward.starts_with(|c: char| c.is_ascii_digit())
+            && outward.starts_with(|c: char| c.is_ascii_uppercase())
+    }
+
+    // "Department of Surgery" or "School of Medicine", but not "Harvard
+    // Medical School"
+    fn is_department(segment: &str) -> bool {
+        let lower = segment.to_lowercase();
+        Self::has_word(segment, DEPARTMENT_WORDS)
+            || lower.starts_with("school of")
+            || lower.starts_with("college of")
+    }
+
+    fn has_word(segment: &str, words: &[&str]) -> bool {
+        segment
+            .split(|c: char| !c.is_alphanumeric())
+            .map(str::to_lowercase)
+            .any(|w| words.contains(&w.as_str()))
+    }
+}
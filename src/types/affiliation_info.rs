@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::helpers::missing_tag_warning;
+use crate::types::affiliation_components::AffiliationComponents;
 use crate::types::identifier::Identifier;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,4 +26,13 @@ impl AffiliationInfo {
         }
         ret
     }
+
+    /// Email addresses, country, postal code, department and institution
+    /// picked out of the `affiliation` text.
+    #[must_use]
+    pub fn components(&self) -> Option<AffiliationComponents> {
+        self.affiliation
+            .as_deref()
+            .map(AffiliationComponents::parse)
+    }
 }
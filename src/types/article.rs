@@ -9,18 +9,19 @@ use crate::types::journal::Journal;
 use crate::types::pagination::Pagination;
 use crate::types::publication_type::PublicationType;
 use crate::types::pubmed_date::PubMedDate;
+use crate::types::rich_text::RichText;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Article {
     pub pub_model: Option<String>,
     pub journal: Option<Journal>,
-    pub title: Option<String>,
-    pub pagination: Vec<Pagination>,
+    pub title: Option<RichText>,
+    pub pagination: Option<Pagination>,
     pub e_location_ids: Vec<ELocationID>,
     pub the_abstract: Option<Abstract>,
     pub author_list: Option<AuthorList>,
     pub language: Option<String>,
-    pub vernacular_title: Option<String>,
+    pub vernacular_title: Option<RichText>,
     pub grant_list: Option<GrantList>,
     pub publication_type_list: Vec<PublicationType>,
     pub article_date: Vec<PubMedDate>,
@@ -42,28 +43,14 @@ impl Article {
         };
         for n in node.children().filter(roxmltree::Node::is_element) {
             match n.tag_name().name() {
-                "ArticleTitle" => ret.title = n.text().map(std::string::ToString::to_string),
+                "ArticleTitle" => ret.title = Some(RichText::new_from_xml(&n)),
                 "Journal" => ret.journal = Some(Journal::new_from_xml(&n)),
-                "Pagination" => {
-                    for n2 in n.children().filter(roxmltree::Node::is_element) {
-                        match n2.tag_name().name() {
-                            "MedlinePgn" => ret
-                                .pagination
-                                .push(Pagination::MedlinePgn(n2.text().unwrap_or("").to_string())),
-                            "StartPage" | "EndPage" => {} // TODO
-                            x => {
-                                missing_tag_warning(&format!("Not covered in Pagination: '{x}'"));
-                            }
-                        }
-                    }
-                }
+                "Pagination" => ret.pagination = Some(Pagination::new_from_xml(&n)),
                 "ELocationID" => ret.e_location_ids.push(ELocationID::new_from_xml(&n)),
                 "Abstract" => ret.the_abstract = Some(Abstract::new_from_xml(&n)),
                 "AuthorList" => ret.author_list = Some(AuthorList::new_from_xml(&n)),
                 "Language" => ret.language = n.text().map(std::string::ToString::to_string),
-                "VernacularTitle" => {
-                    ret.vernacular_title = n.text().map(std::string::ToString::to_string)
-                }
+                "VernacularTitle" => ret.vernacular_title = Some(RichText::new_from_xml(&n)),
                 "GrantList" => ret.grant_list = Some(GrantList::new_from_xml(&n)),
                 "ArticleDate" => {
                     if let Some(date) = PubMedDate::new_from_xml(&n) {
@@ -1,19 +1,75 @@
 use serde::{Deserialize, Serialize};
 
+use crate::helpers::missing_tag_warning;
+use crate::types::rich_text::RichText;
+
+/// One `AbstractText` element. Structured abstracts have one section per
+/// heading, e.g. `Label="METHODS"` with `NlmCategory="METHODS"`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AbstractSection {
+    pub label: Option<String>,
+    pub nlm_category: Option<String>,
+    pub text: RichText,
+}
+
+impl AbstractSection {
+    #[must_use]
+    pub fn new_from_xml(node: &roxmltree::Node) -> Self {
+        Self {
+            label: node
+                .attribute("Label")
+                .map(std::string::ToString::to_string),
+            nlm_category: node
+                .attribute("NlmCategory")
+                .map(std::string::ToString::to_string),
+            text: RichText::new_from_xml(node).trim(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Abstract {
-    pub text: Option<String>,
+    pub sections: Vec<AbstractSection>,
+    pub copyright_information: Option<String>,
 }
 
 impl Abstract {
-    #[must_use] 
+    #[must_use]
     pub fn new_from_xml(node: &roxmltree::Node) -> Self {
-        Self {
-            text: node
-                .descendants()
-                .filter(|n| n.is_element() && n.tag_name().name() == "AbstractText")
-                .map(|n| n.text().unwrap_or("").to_string())
-                .next(),
+        let mut ret = Self {
+            sections: vec![],
+            copyright_information: None,
+        };
+        for n in node.children().filter(roxmltree::Node::is_element) {
+            match n.tag_name().name() {
+                "AbstractText" => ret.sections.push(AbstractSection::new_from_xml(&n)),
+                "CopyrightInformation" => {
+                    ret.copyright_information = n.text().map(|s| s.trim().to_string());
+                }
+                x => missing_tag_warning(&format!("Not covered in Abstract: '{x}'")),
+            }
+        }
+        ret
+    }
+
+    /// The full abstract as plain text, one line per section, each prefixed
+    /// with its label (e.g. `METHODS: ...`) if it has one. `None` if there
+    /// is no text.
+    #[must_use]
+    pub fn text(&self) -> Option<String> {
+        let lines: Vec<String> = self
+            .sections
+            .iter()
+            .filter(|s| !s.text.is_empty())
+            .map(|s| match &s.label {
+                Some(label) => format!("{label}: {}", s.text),
+                None => s.text.plain_text(),
+            })
+            .collect();
+        if lines.is_empty() {
+            None
+        } else {
+            Some(lines.join("\n"))
         }
     }
 }
@@ -2,12 +2,114 @@ use serde::{Deserialize, Serialize};
 
 use crate::helpers::missing_tag_warning;
 
+/// The kind of an article identifier, from the `IdType`/`EIdType` attributes.
+/// Serializes as the lower-case PubMed name, e.g. `"doi"`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum ArticleIdType {
+    Pubmed,
+    Doi,
+    Pmc,
+    Pii,
+    Mid,
+    Sici,
+    Medline,
+    Other(String),
+}
+
+impl ArticleIdType {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            ArticleIdType::Pubmed => "pubmed",
+            ArticleIdType::Doi => "doi",
+            ArticleIdType::Pmc => "pmc",
+            ArticleIdType::Pii => "pii",
+            ArticleIdType::Mid => "mid",
+            ArticleIdType::Sici => "sici",
+            ArticleIdType::Medline => "medline",
+            ArticleIdType::Other(s) => s,
+        }
+    }
+
+    /// Normalizes an ID of this kind: DOIs are lower-cased and stripped of
+    /// `doi:`/resolver prefixes, PMC IDs get an upper-case `PMC` prefix.
+    #[must_use]
+    pub fn normalize(&self, id: &str) -> String {
+        let id = id.trim();
+        match self {
+            ArticleIdType::Doi => {
+                let lower = id.to_lowercase();
+                [
+                    "https://doi.org/",
+                    "http://doi.org/",
+                    "https://dx.doi.org/",
+                    "http://dx.doi.org/",
+                    "doi:",
+                ]
+                .iter()
+                .find_map(|prefix| lower.strip_prefix(prefix))
+                .unwrap_or(&lower)
+                .trim()
+                .to_string()
+            }
+            ArticleIdType::Pmc => {
+                let digits = match id.get(..3) {
+                    Some(prefix) if prefix.eq_ignore_ascii_case("pmc") => &id[3..],
+                    _ => id,
+                };
+                format!("PMC{}", digits.trim())
+            }
+            _ => id.to_string(),
+        }
+    }
+}
+
+impl From<&str> for ArticleIdType {
+    fn from(s: &str) -> Self {
+        match s.trim().to_lowercase().as_str() {
+            "pubmed" => ArticleIdType::Pubmed,
+            "doi" => ArticleIdType::Doi,
+            "pmc" => ArticleIdType::Pmc,
+            "pii" => ArticleIdType::Pii,
+            "mid" => ArticleIdType::Mid,
+            "sici" => ArticleIdType::Sici,
+            "medline" => ArticleIdType::Medline,
+            _ => ArticleIdType::Other(s.to_string()),
+        }
+    }
+}
+
+impl From<String> for ArticleIdType {
+    fn from(s: String) -> Self {
+        Self::from(s.as_str())
+    }
+}
+
+impl From<ArticleIdType> for String {
+    fn from(id_type: ArticleIdType) -> Self {
+        id_type.as_str().to_string()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArticleId {
-    pub id_type: Option<String>,
+    pub id_type: Option<ArticleIdType>,
     pub id: Option<String>,
 }
 
+impl ArticleId {
+    /// The ID, normalized according to its type.
+    #[must_use]
+    pub fn normalized(&self) -> Option<String> {
+        let id = self.id.as_deref()?;
+        Some(match &self.id_type {
+            Some(id_type) => id_type.normalize(id),
+            None => id.trim().to_string(),
+        })
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArticleIdList {
     pub ids: Vec<ArticleId>,
@@ -19,7 +121,7 @@ impl ArticleIdList {
         for n in node.children().filter(roxmltree::Node::is_element) {
             match n.tag_name().name() {
                 "ArticleId" => ret.ids.push(ArticleId {
-                    id_type: n.attribute("IdType").map(std::string::ToString::to_string),
+                    id_type: n.attribute("IdType").map(ArticleIdType::from),
                     id: n.text().map(std::string::ToString::to_string),
                 }),
                 x => missing_tag_warning(&format!("Not covered in ArticleIdList: '{x}'")),
@@ -27,4 +129,38 @@ impl ArticleIdList {
         }
         ret
     }
+
+    /// Returns the first normalized ID of the given type.
+    #[must_use]
+    pub fn get(&self, id_type: &ArticleIdType) -> Option<String> {
+        self.ids
+            .iter()
+            .filter(|id| id.id_type.as_ref() == Some(id_type))
+            .find_map(ArticleId::normalized)
+    }
+
+    #[must_use]
+    pub fn pmid(&self) -> Option<u64> {
+        self.get(&ArticleIdType::Pubmed)?.parse::<u64>().ok()
+    }
+
+    #[must_use]
+    pub fn doi(&self) -> Option<String> {
+        self.get(&ArticleIdType::Doi)
+    }
+
+    #[must_use]
+    pub fn pmcid(&self) -> Option<String> {
+        self.get(&ArticleIdType::Pmc)
+    }
+
+    #[must_use]
+    pub fn pii(&self) -> Option<String> {
+        self.get(&ArticleIdType::Pii)
+    }
+
+    #[must_use]
+    pub fn mid(&self) -> Option<String> {
+        self.get(&ArticleIdType::Mid)
+    }
 }
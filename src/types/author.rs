@@ -11,7 +11,7 @@ pub struct Author {
     pub initials: Option<String>,
     pub suffix: Option<String>,
     pub collective_name: Option<String>,
-    pub affiliation_info: Option<AffiliationInfo>,
+    pub affiliation_info: Vec<AffiliationInfo>,
     pub identifiers: Vec<Identifier>,
     pub valid: bool,
 }
@@ -25,7 +25,7 @@ impl Author {
             initials: None,
             suffix: None,
             collective_name: None,
-            affiliation_info: None,
+            affiliation_info: vec![],
             identifiers: vec![],
             valid: node.attribute("ValidYN") == Some("Y"),
         };
@@ -37,12 +37,19 @@ impl Author {
                 "Initials" => ret.initials = n.text().map(std::string::ToString::to_string),
                 "Suffix" => ret.suffix = n.text().map(std::string::ToString::to_string),
                 "Identifier" => ret.identifiers.push(Identifier::new_from_xml(&n)),
-                "AffiliationInfo" => ret.affiliation_info = Some(AffiliationInfo::new_from_xml(&n)),
+                "AffiliationInfo" => ret.affiliation_info.push(AffiliationInfo::new_from_xml(&n)),
                 x => missing_tag_warning(&format!("Not covered in Author: '{x}'")),
             }
         }
         ret
     }
+
+    /// The affiliation texts, in document order.
+    pub fn affiliations(&self) -> impl Iterator<Item = &str> {
+        self.affiliation_info
+            .iter()
+            .filter_map(|a| a.affiliation.as_deref())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -0,0 +1,129 @@
+use serde::{Deserialize, Serialize};
+
+use crate::types::reference::Reference;
+
+/// The structured parts of a citation, to be resolved to a PMID through
+/// ECitMatch. `key` is an arbitrary caller-chosen label echoed back in the
+/// matching [`CitationMatch`].
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CitationKey {
+    pub journal: Option<String>,
+    pub year: Option<String>,
+    pub volume: Option<String>,
+    pub first_page: Option<String>,
+    pub author_name: Option<String>,
+    pub key: String,
+}
+
+impl CitationKey {
+    /// Best-effort parse of a free-text citation in the usual PubMed style,
+    /// e.g. "Mann BJ, Petri WA Jr. Title. Proc Natl Acad Sci U S A.
+    /// 1991;88(8):3248-52." Returns `None` if no "year;volume:pages" part is
+    /// found.
+    #[must_use]
+    pub fn from_citation(citation: &str, key: impl Into<String>) -> Option<Self> {
+        let sentences: Vec<&str> = citation.split(". ").map(str::trim).collect();
+        let year_prefix = |s: &str| {
+            s.get(..4)
+                .filter(|y| y.chars().all(|c| c.is_ascii_digit()))
+                .map(str::to_string)
+        };
+        let source_pos = sentences
+            .iter()
+            .position(|s| year_prefix(s).is_some() && s.contains(';'))?;
+        let source = sentences[source_pos].trim_end_matches('.');
+        let year = year_prefix(source)?;
+        let (_, volume_pages) = source.split_once(';')?;
+        let (volume, pages) = volume_pages.split_once(':').unwrap_or((volume_pages, ""));
+        let volume = volume.split('(').next().unwrap_or(volume).trim();
+        let first_page = pages.split(['-', '–', ',']).next().unwrap_or("").trim();
+        let journal = match source_pos {
+            0 => None,
+            pos => Some(sentences[pos - 1].trim_end_matches('.')),
+        };
+        let author_name = match source_pos {
+            0 | 1 => None,
+            _ => sentences[0].split(',').next().map(str::trim),
+        };
+        let non_empty = |s: &str| (!s.is_empty()).then(|| s.to_string());
+        Some(Self {
+            journal: journal.and_then(non_empty),
+            year: Some(year),
+            volume: non_empty(volume),
+            first_page: non_empty(first_page),
+            author_name: author_name.and_then(non_empty),
+            key: key.into(),
+        })
+    }
+
+    /// Builds a key from a [`Reference`]'s citation string, see
+    /// [`CitationKey::from_citation`].
+    #[must_use]
+    pub fn from_reference(reference: &Reference, key: impl Into<String>) -> Option<Self> {
+        Self::from_citation(&reference.citation.as_ref()?.plain_text(), key)
+    }
+
+    /// Renders this key as one ECitMatch `bdata` line:
+    /// `journal|year|volume|first_page|author|key|`, with `key` sent in
+    /// place of `self.key`.
+    pub(crate) fn to_bdata(&self, key: &str) -> String {
+        let field = |s: &Option<String>| s.as_deref().unwrap_or("").replace(['|', '\r'], " ");
+        format!(
+            "{}|{}|{}|{}|{}|{}|",
+            field(&self.journal),
+            field(&self.year),
+            field(&self.volume),
+            field(&self.first_page),
+            field(&self.author_name),
+            key.replace(['|', '\r'], " "),
+        )
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CitationMatchStatus {
+    Found(u64),
+    NotFound,
+    Ambiguous,
+}
+
+/// The ECitMatch result for one [`CitationKey`]. `detail` holds the raw
+/// status text for citations that did not resolve to a single PMID.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CitationMatch {
+    pub key: String,
+    pub status: CitationMatchStatus,
+    pub detail: Option<String>,
+}
+
+impl CitationMatch {
+    /// Parses one line of ECitMatch output, i.e. the submitted `bdata` line
+    /// followed by the PMID or a `NOT_FOUND`/`AMBIGUOUS` status.
+    pub(crate) fn new_from_line(line: &str) -> Option<Self> {
+        let parts: Vec<&str> = line.trim_end_matches(['\r', '\n']).split('|').collect();
+        if parts.len() < 7 {
+            return None;
+        }
+        let result = parts[6].trim();
+        let (status, detail) = if let Ok(pmid) = result.parse::<u64>() {
+            (CitationMatchStatus::Found(pmid), None)
+        } else if result.starts_with("AMBIGUOUS") {
+            (CitationMatchStatus::Ambiguous, Some(result.to_string()))
+        } else {
+            (CitationMatchStatus::NotFound, Some(result.to_string()))
+        };
+        Some(Self {
+            key: parts[5].to_string(),
+            status,
+            detail: detail.filter(|d| !d.is_empty()),
+        })
+    }
+
+    #[must_use]
+    pub fn pmid(&self) -> Option<u64> {
+        match self.status {
+            CitationMatchStatus::Found(pmid) => Some(pmid),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::{json_string, json_u64};
+use crate::types::pubmed_date::PubMedDate;
+
+/// Short PubMed search tags and the EInfo field names they stand for.
+const FIELD_TAG_ALIASES: &[(&str, &str)] = &[
+    ("1au", "FAUT"),
+    ("ad", "AFFL"),
+    ("au", "AUTH"),
+    ("auid", "AUID"),
+    ("book", "BOOK"),
+    ("cn", "COLN"),
+    ("cois", "COIS"),
+    ("crdt", "CRDT"),
+    ("dcom", "CDAT"),
+    ("dep", "EPDT"),
+    ("dp", "PDAT"),
+    ("ed", "ED"),
+    ("edat", "EDAT"),
+    ("fau", "FULL"),
+    ("filter", "FILT"),
+    ("fir", "FINV"),
+    ("gr", "GRNT"),
+    ("ip", "ISS"),
+    ("ir", "INVR"),
+    ("isbn", "ISBN"),
+    ("ta", "JOUR"),
+    ("journal", "JOUR"),
+    ("la", "LANG"),
+    ("lastau", "LAUT"),
+    ("lid", "LID"),
+    ("lr", "MDAT"),
+    ("majr", "MAJR"),
+    ("mh", "MESH"),
+    ("mhda", "MHDA"),
+    ("nm", "SUBS"),
+    ("ot", "OTRM"),
+    ("pa", "PAPX"),
+    ("pg", "PAGE"),
+    ("pl", "CNTY"),
+    ("pmid", "UID"),
+    ("ppdt", "PPDT"),
+    ("ps", "PS"),
+    ("pt", "PTYP"),
+    ("pubn", "PUBN"),
+    ("rn", "ECNO"),
+    ("sb", "FILT"),
+    ("sh", "SUBH"),
+    ("si", "SI"),
+    ("ti", "TITL"),
+    ("tiab", "TIAB"),
+    ("tt", "TT"),
+    ("tw", "WORD"),
+    ("vi", "VOL"),
+];
+
+/// A searchable field of an Entrez database, as reported by EInfo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchField {
+    pub name: String,
+    pub full_name: Option<String>,
+    pub description: Option<String>,
+    pub term_count: Option<u64>,
+    pub is_date: bool,
+    pub is_numerical: bool,
+    pub single_token: bool,
+    pub hierarchy: bool,
+    pub is_hidden: bool,
+}
+
+/// A link name available from an Entrez database, as reported by EInfo.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkInfo {
+    pub name: String,
+    pub menu: Option<String>,
+    pub description: Option<String>,
+    pub db_to: Option<String>,
+}
+
+/// Statistics, search fields and link names of an Entrez database.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseInfo {
+    pub db_name: String,
+    pub menu_name: Option<String>,
+    pub description: Option<String>,
+    pub db_build: Option<String>,
+    pub count: u64,
+    pub last_update: Option<PubMedDate>,
+    pub fields: Vec<SearchField>,
+    pub links: Vec<LinkInfo>,
+}
+
+impl DatabaseInfo {
+    /// Parses an EInfo version 2.0 JSON response for a single database.
+    pub(crate) fn new_from_json(json: &serde_json::Value) -> Option<Self> {
+        let dbinfo = &json.get("einforesult")?["dbinfo"];
+        // `dbinfo` is a one-element array, or a plain object in older output
+        let dbinfo = dbinfo.as_array().map_or(Some(dbinfo), |a| a.first())?;
+        Some(Self {
+            db_name: dbinfo["dbname"].as_str()?.to_string(),
+            menu_name: json_string(&dbinfo["menuname"]),
+            description: json_string(&dbinfo["description"]),
+            db_build: json_string(&dbinfo["dbbuild"]),
+            count: json_u64(&dbinfo["count"]).unwrap_or(0),
+            last_update: dbinfo["lastupdate"]
+                .as_str()
+                .and_then(|d| PubMedDate::new_from_esummary_str(d, None)),
+            fields: dbinfo["fieldlist"]
+                .as_array()
+                .map(|a| a.iter().filter_map(Self::field_from_json).collect())
+                .unwrap_or_default(),
+            links: dbinfo["linklist"]
+                .as_array()
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|l| {
+                            Some(LinkInfo {
+                                name: l["name"].as_str()?.to_string(),
+                                menu: json_string(&l["menu"]),
+                                description: json_string(&l["description"]),
+                                db_to: json_string(&l["dbto"]),
+                            })
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Parses the database list of an EInfo response without `db`.
+    pub(crate) fn db_list_from_json(json: &serde_json::Value) -> Option<Vec<String>> {
+        Some(
+            json.get("einforesult")?["dblist"]
+                .as_array()?
+                .iter()
+                .filter_map(|db| db.as_str().map(std::string::ToString::to_string))
+                .collect(),
+        )
+    }
+
+    /// Looks up the field for a search tag such as `[au]`, `mh`, `[MeSH Terms]`
+    /// or `[mh:noexp]`. Matches field names, full names and the common short
+    /// PubMed tags, case-insensitively.
+    #[must_use]
+    pub fn field_for_tag(&self, tag: &str) -> Option<&SearchField> {
+        let tag = tag.trim().trim_start_matches('[').trim_end_matches(']');
+        let tag = tag.split(':').next().unwrap_or(tag).trim().to_lowercase();
+        let alias = FIELD_TAG_ALIASES
+            .iter()
+            .find(|(short, _)| *short == tag)
+            .map(|(_, name)| *name);
+        self.fields.iter().find(|f| {
+            f.name.to_lowercase() == tag
+                || f.full_name
+                    .as_ref()
+                    .is_some_and(|n| n.to_lowercase() == tag)
+                || alias.is_some_and(|a| a == f.name)
+        })
+    }
+
+    /// Returns the bracketed field tags in `query` that this database does not
+    /// know about.
+    #[must_use]
+    pub fn unknown_field_tags(&self, query: &str) -> Vec<String> {
+        query
+            .split('[')
+            .skip(1)
+            .filter_map(|part| part.split_once(']').map(|(tag, _)| tag))
+            .filter(|tag| self.field_for_tag(tag).is_none())
+            .map(|tag| format!("[{tag}]"))
+            .collect()
+    }
+
+    fn field_from_json(json: &serde_json::Value) -> Option<SearchField> {
+        Some(SearchField {
+            name: json["name"].as_str()?.to_string(),
+            full_name: json_string(&json["fullname"]),
+            description: json_string(&json["description"]),
+            term_count: json_u64(&json["termcount"]),
+            is_date: Self::json_flag(&json["isdate"]),
+            is_numerical: Self::json_flag(&json["isnumerical"]),
+            single_token: Self::json_flag(&json["singletoken"]),
+            hierarchy: Self::json_flag(&json["hierarchy"]),
+            is_hidden: Self::json_flag(&json["ishidden"]),
+        })
+    }
+
+    // EInfo flags are "Y"/"N" strings
+    fn json_flag(value: &serde_json::Value) -> bool {
+        value.as_str() == Some("Y") || value.as_bool() == Some(true)
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::{json_string, json_strings};
+use crate::types::article_id::{ArticleId, ArticleIdList, ArticleIdType};
+use crate::types::author::Author;
+use crate::types::pubmed_date::PubMedDate;
+
+/// A PubMed document summary (DocSum) as returned by ESummary version 2.0.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DocumentSummary {
+    pub uid: u64,
+    pub title: Option<String>,
+    pub vernacular_title: Option<String>,
+    pub authors: Vec<Author>,
+    pub last_author: Option<String>,
+    pub source: Option<String>,
+    pub full_journal_name: Option<String>,
+    pub nlm_unique_id: Option<String>,
+    pub issn: Option<String>,
+    pub essn: Option<String>,
+    pub volume: Option<String>,
+    pub issue: Option<String>,
+    pub pages: Option<String>,
+    pub elocation_id: Option<String>,
+    pub pub_date: Option<String>,
+    pub epub_date: Option<String>,
+    pub sort_pub_date: Option<PubMedDate>,
+    pub languages: Vec<String>,
+    pub publication_types: Vec<String>,
+    pub attributes: Vec<String>,
+    pub article_ids: ArticleIdList,
+    pub history: Vec<PubMedDate>,
+    pub pmc_ref_count: Option<u64>,
+}
+
+impl DocumentSummary {
+    /// Parses all summaries in an ESummary JSON response, in the order of
+    /// its `uids` list. UIDs that ESummary reports an error for are skipped.
+    pub(crate) fn new_vec_from_json(json: &serde_json::Value) -> Option<Vec<Self>> {
+        let result = json.get("result")?;
+        Some(
+            result["uids"]
+                .as_array()?
+                .iter()
+                .filter_map(serde_json::Value::as_str)
+                .filter_map(|uid| Self::new_from_json(&result[uid]))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn new_from_json(json: &serde_json::Value) -> Option<Self> {
+        if json.get("error").is_some() {
+            return None;
+        }
+        let uid = json["uid"].as_str()?.parse::<u64>().ok()?;
+        Some(Self {
+            uid,
+            title: json_string(&json["title"]),
+            vernacular_title: json_string(&json["vernaculartitle"]),
+            authors: json["authors"]
+                .as_array()
+                .map(|a| a.iter().filter_map(Self::author_from_json).collect())
+                .unwrap_or_default(),
+            last_author: json_string(&json["lastauthor"]),
+            source: json_string(&json["source"]),
+            full_journal_name: json_string(&json["fulljournalname"]),
+            nlm_unique_id: json_string(&json["nlmuniqueid"]),
+            issn: json_string(&json["issn"]),
+            essn: json_string(&json["essn"]),
+            volume: json_string(&json["volume"]),
+            issue: json_string(&json["issue"]),
+            pages: json_string(&json["pages"]),
+            elocation_id: json_string(&json["elocationid"]),
+            pub_date: json_string(&json["pubdate"]),
+            epub_date: json_string(&json["epubdate"]),
+            sort_pub_date: json["sortpubdate"]
+                .as_str()
+                .and_then(|d| PubMedDate::new_from_esummary_str(d, None)),
+            languages: json_strings(&json["lang"]),
+            publication_types: json_strings(&json["pubtype"]),
+            attributes: json_strings(&json["attributes"]),
+            article_ids: ArticleIdList {
+                ids: json["articleids"]
+                    .as_array()
+                    .map(|a| {
+                        a.iter()
+                            .map(|id| ArticleId {
+                                id_type: id["idtype"].as_str().map(ArticleIdType::from),
+                                id: json_string(&id["value"]),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            },
+            history: json["history"]
+                .as_array()
+                .map(|a| {
+                    a.iter()
+                        .filter_map(|h| {
+                            PubMedDate::new_from_esummary_str(
+                                h["date"].as_str()?,
+                                json_string(&h["pubstatus"]),
+                            )
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            pmc_ref_count: json["pmcrefcount"]
+                .as_u64()
+                .or_else(|| json["pmcrefcount"].as_str()?.parse::<u64>().ok()),
+        })
+    }
+
+    // ESummary gives names as "Lastname Initials", e.g. "Smith JA"
+    fn author_from_json(json: &serde_json::Value) -> Option<Author> {
+        let name = json["name"].as_str()?.trim();
+        if name.is_empty() {
+            return None;
+        }
+        let mut author = Author {
+            last_name: None,
+            fore_name: None,
+            initials: None,
+            suffix: None,
+            collective_name: None,
+            affiliation_info: vec![],
+            identifiers: vec![],
+            valid: true,
+        };
+        if json["authtype"].as_str() == Some("CollectiveName") {
+            author.collective_name = Some(name.to_string());
+        } else if let Some((last_name, initials)) = name.rsplit_once(' ') {
+            author.last_name = Some(last_name.to_string());
+            author.initials = Some(initials.to_string());
+        } else {
+            author.last_name = Some(name.to_string());
+        }
+        Some(author)
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::types::article_id::ArticleIdType;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ELocationID {
     pub e_id_type: Option<String>,
@@ -16,4 +18,36 @@ impl ELocationID {
             id: node.text().map(std::string::ToString::to_string),
         }
     }
+
+    /// The typed `EIdType`, e.g. [`ArticleIdType::Doi`].
+    #[must_use]
+    pub fn id_type(&self) -> Option<ArticleIdType> {
+        self.e_id_type.as_deref().map(ArticleIdType::from)
+    }
+
+    /// The ID, normalized according to its type.
+    #[must_use]
+    pub fn normalized(&self) -> Option<String> {
+        let id = self.id.as_deref()?;
+        Some(match self.id_type() {
+            Some(id_type) => id_type.normalize(id),
+            None => id.trim().to_string(),
+        })
+    }
+
+    #[must_use]
+    pub fn doi(&self) -> Option<String> {
+        match self.id_type()? {
+            ArticleIdType::Doi => self.normalized(),
+            _ => None,
+        }
+    }
+
+    #[must_use]
+    pub fn pii(&self) -> Option<String> {
+        match self.id_type()? {
+            ArticleIdType::Pii => self.normalized(),
+            _ => None,
+        }
+    }
 }
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+/// A result set stored on the Entrez History server, addressed by `WebEnv`
+/// and `query_key`. Returned by [`crate::Client::search_history`] and
+/// [`crate::Client::epost`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryHandle {
+    pub web_env: String,
+    pub query_key: String,
+    pub count: u64,
+}
+
+impl HistoryHandle {
+    /// Parses an ESearch JSON response run with `usehistory=y`.
+    pub(crate) fn new_from_esearch_json(json: &serde_json::Value) -> Option<Self> {
+        let result = json.get("esearchresult")?;
+        Some(Self {
+            web_env: result["webenv"].as_str()?.to_string(),
+            query_key: result["querykey"].as_str()?.to_string(),
+            count: result["count"]
+                .as_str()
+                .and_then(|s| s.parse::<u64>().ok())
+                .unwrap_or(0),
+        })
+    }
+
+    /// Parses an EPost XML response. EPost does not echo the number of
+    /// stored IDs, so the caller supplies `count`.
+    pub(crate) fn new_from_epost_xml(node: &roxmltree::Node, count: u64) -> Option<Self> {
+        let text_of = |name: &str| {
+            node.descendants()
+                .find(|n| n.is_element() && n.tag_name().name() == name)
+                .and_then(|n| n.text())
+                .map(|t| t.trim().to_string())
+        };
+        Some(Self {
+            web_env: text_of("WebEnv")?,
+            query_key: text_of("QueryKey")?,
+            count,
+        })
+    }
+}
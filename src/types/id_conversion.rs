@@ -0,0 +1,168 @@
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::{json_string, json_u64};
+use crate::types::article_id::ArticleIdType;
+
+/// The kinds of identifiers understood by the PMC ID Converter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ConverterIdType {
+    Pmid,
+    Pmcid,
+    Doi,
+    /// NIH manuscript ID, e.g. `NIHMS1677310`
+    Mid,
+}
+
+impl ConverterIdType {
+    /// Guesses the type of `id` from its format. DOIs may carry a `doi:`
+    /// or resolver prefix, see [`ConverterIdType::normalize`].
+    #[must_use]
+    pub fn detect(id: &str) -> Option<Self> {
+        let id = id.trim();
+        let upper = id.to_uppercase();
+        let doi = ArticleIdType::Doi.normalize(id);
+        if !id.is_empty() && id.chars().all(|c| c.is_ascii_digit()) {
+            Some(Self::Pmid)
+        } else if upper.starts_with("PMC") {
+            Some(Self::Pmcid)
+        } else if doi.starts_with("10.") && doi.contains('/') {
+            Some(Self::Doi)
+        } else if upper.starts_with("NIHMS")
+            || upper.starts_with("EMS")
+            || upper.starts_with("HHMIMS")
+            || upper.starts_with("AMS")
+            || upper.starts_with("CAMS")
+        {
+            Some(Self::Mid)
+        } else {
+            None
+        }
+    }
+
+    /// The form of `id` sent to the converter; DOIs are stripped of
+    /// `doi:` and resolver prefixes, as in [`ArticleIdType::normalize`].
+    #[must_use]
+    pub fn normalize(&self, id: &str) -> String {
+        match self {
+            Self::Doi => ArticleIdType::Doi.normalize(id),
+            _ => id.trim().to_string(),
+        }
+    }
+
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Pmid => "pmid",
+            Self::Pmcid => "pmcid",
+            Self::Doi => "doi",
+            Self::Mid => "mid",
+        }
+    }
+}
+
+/// One version of a PMC article.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdVersion {
+    pub pmcid: Option<String>,
+    pub mid: Option<String>,
+    pub current: bool,
+    pub live: Option<bool>,
+    pub release_date: Option<String>,
+}
+
+/// The identifiers of one article as returned by the PMC ID Converter.
+/// `requested_id` is the ID the caller asked for; `error` is set if the
+/// converter could not resolve it.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct IdRecord {
+    pub requested_id: String,
+    pub pmid: Option<u64>,
+    pub pmcid: Option<String>,
+    pub doi: Option<String>,
+    pub mid: Option<String>,
+    pub live: Option<bool>,
+    pub release_date: Option<String>,
+    pub versions: Vec<IdVersion>,
+    pub error: Option<String>,
+}
+
+impl IdRecord {
+    /// Parses the `records` of an ID Converter JSON response.
+    pub(crate) fn new_vec_from_json(json: &serde_json::Value) -> Option<Vec<Self>> {
+        Some(
+            json.get("records")?
+                .as_array()?
+                .iter()
+                .map(Self::new_from_json)
+                .collect(),
+        )
+    }
+
+    fn new_from_json(json: &serde_json::Value) -> Self {
+        let error = if json["status"].as_str() == Some("error") {
+            Some(json_string(&json["errmsg"]).unwrap_or_else(|| "unknown error".to_string()))
+        } else {
+            None
+        };
+        Self {
+            requested_id: json_string(&json["requested-id"]).unwrap_or_default(),
+            pmid: json_u64(&json["pmid"]),
+            pmcid: json_string(&json["pmcid"]),
+            doi: json_string(&json["doi"]),
+            mid: json_string(&json["mid"]),
+            live: Self::json_bool(&json["live"]),
+            release_date: json_string(&json["release-date"]),
+            versions: json["versions"]
+                .as_array()
+                .map(|a| {
+                    a.iter()
+                        .map(|v| IdVersion {
+                            pmcid: json_string(&v["pmcid"]),
+                            mid: json_string(&v["mid"]),
+                            current: Self::json_bool(&v["current"]).unwrap_or(false),
+                            live: Self::json_bool(&v["live"]),
+                            release_date: json_string(&v["release-date"]),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default(),
+            error,
+        }
+    }
+
+    /// The normalized form of a requested ID, as used by
+    /// [`IdRecord::lookup_keys`].
+    pub(crate) fn lookup_key(id: &str) -> String {
+        id.trim().to_ascii_uppercase()
+    }
+
+    /// All normalized IDs of type `id_type` a request could use for this
+    /// record; a PMCID is also found without its "PMC" prefix.
+    pub(crate) fn lookup_keys(&self, id_type: ConverterIdType) -> Vec<String> {
+        let mut ret = vec![Self::lookup_key(&self.requested_id)];
+        match id_type {
+            ConverterIdType::Pmid => ret.extend(self.pmid.map(|p| p.to_string())),
+            ConverterIdType::Pmcid => {
+                if let Some(pmcid) = &self.pmcid {
+                    let pmcid = Self::lookup_key(pmcid);
+                    if let Some(number) = pmcid.strip_prefix("PMC") {
+                        ret.push(number.to_string());
+                    }
+                    ret.push(pmcid);
+                }
+            }
+            ConverterIdType::Doi => ret.extend(self.doi.as_deref().map(Self::lookup_key)),
+            ConverterIdType::Mid => ret.extend(self.mid.as_deref().map(Self::lookup_key)),
+        }
+        ret
+    }
+
+    // The converter uses "true"/"false" strings
+    fn json_bool(value: &serde_json::Value) -> Option<bool> {
+        value.as_bool().or_else(|| match value.as_str()? {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        })
+    }
+}
@@ -0,0 +1,134 @@
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::json_u64;
+
+/// An ELink link name, e.g. `pubmed_pubmed_citedin`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum LinkName {
+    /// `pubmed_pubmed`: similar articles, with relevance scores
+    SimilarArticles,
+    /// `pubmed_pubmed_citedin`: PubMed articles citing the source article
+    CitedIn,
+    /// `pubmed_pubmed_refs`: PubMed articles cited by the source article
+    References,
+    /// `pubmed_pmc`: the full-text record(s) in PubMed Central
+    Pmc,
+    Other(String),
+}
+
+impl LinkName {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match self {
+            LinkName::SimilarArticles => "pubmed_pubmed",
+            LinkName::CitedIn => "pubmed_pubmed_citedin",
+            LinkName::References => "pubmed_pubmed_refs",
+            LinkName::Pmc => "pubmed_pmc",
+            LinkName::Other(name) => name,
+        }
+    }
+
+    #[must_use]
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "pubmed_pubmed" => LinkName::SimilarArticles,
+            "pubmed_pubmed_citedin" => LinkName::CitedIn,
+            "pubmed_pubmed_refs" => LinkName::References,
+            "pubmed_pmc" => LinkName::Pmc,
+            other => LinkName::Other(other.to_string()),
+        }
+    }
+
+    /// The Entrez database the link points to, taken from the
+    /// `dbfrom_dbto[_subset]` link name.
+    #[must_use]
+    pub fn target_db(&self) -> &str {
+        self.as_str().split('_').nth(1).unwrap_or("pubmed")
+    }
+}
+
+/// A single linked record; `score` is only set for scored links such as
+/// similar articles.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Link {
+    pub id: u64,
+    pub score: Option<u64>,
+}
+
+/// The links of one type from a source record.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkSetDb {
+    pub link_name: LinkName,
+    pub db_to: String,
+    pub links: Vec<Link>,
+}
+
+/// All requested links for one source PMID.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LinkSet {
+    pub source_id: u64,
+    pub link_set_dbs: Vec<LinkSetDb>,
+}
+
+impl LinkSet {
+    /// Returns the links of the given type, or an empty slice.
+    #[must_use]
+    pub fn links(&self, link_name: &LinkName) -> &[Link] {
+        self.link_set_dbs
+            .iter()
+            .find(|lsd| lsd.link_name == *link_name)
+            .map_or(&[], |lsd| lsd.links.as_slice())
+    }
+
+    /// Parses the `linksets` of an ELink JSON response.
+    pub(crate) fn new_vec_from_json(json: &serde_json::Value) -> Option<Vec<Self>> {
+        Some(
+            json.get("linksets")?
+                .as_array()?
+                .iter()
+                .filter_map(Self::new_from_json)
+                .collect(),
+        )
+    }
+
+    fn new_from_json(json: &serde_json::Value) -> Option<Self> {
+        let source_id = json_u64(json["ids"].as_array()?.first()?)?;
+        let link_set_dbs = json["linksetdbs"]
+            .as_array()
+            .map(|dbs| {
+                dbs.iter()
+                    .filter_map(|db| {
+                        Some(LinkSetDb {
+                            link_name: LinkName::from_name(db["linkname"].as_str()?),
+                            db_to: db["dbto"].as_str().unwrap_or_default().to_string(),
+                            links: db["links"]
+                                .as_array()?
+                                .iter()
+                                .filter_map(Self::link_from_json)
+                                .collect(),
+                        })
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        Some(Self {
+            source_id,
+            link_set_dbs,
+        })
+    }
+
+    // Links are plain IDs, or `{"id":..,"score":..}` objects with cmd=neighbor_score
+    fn link_from_json(json: &serde_json::Value) -> Option<Link> {
+        if json.is_object() {
+            Some(Link {
+                id: json_u64(&json["id"])?,
+                score: json_u64(&json["score"]),
+            })
+        } else {
+            Some(Link {
+                id: json_u64(json)?,
+                score: None,
+            })
+        }
+    }
+}
@@ -1,15 +1,22 @@
+pub mod affiliation_components;
 pub mod affiliation_info;
 pub mod article;
 pub mod article_abstract;
 pub mod article_id;
 pub mod author;
 pub mod chemical;
+pub mod citation_match;
+pub mod database_info;
+pub mod document_summary;
 pub mod elocation_id;
 pub mod grant;
+pub mod history;
+pub mod id_conversion;
 pub mod identifier;
 pub mod journal;
 pub mod journal_issue;
 pub mod keyword;
+pub mod link;
 pub mod medline_citation;
 pub mod medline_journal_info;
 pub mod mesh;
@@ -20,26 +27,39 @@ pub mod pubmed_article;
 pub mod pubmed_data;
 pub mod pubmed_date;
 pub mod reference;
+pub mod rich_text;
+pub mod search_result;
+pub mod spelling;
 
+pub use affiliation_components::AffiliationComponents;
 pub use affiliation_info::AffiliationInfo;
 pub use article::Article;
-pub use article_abstract::Abstract;
-pub use article_id::{ArticleId, ArticleIdList};
+pub use article_abstract::{Abstract, AbstractSection};
+pub use article_id::{ArticleId, ArticleIdList, ArticleIdType};
 pub use author::{Author, AuthorList};
 pub use chemical::Chemical;
+pub use citation_match::{CitationKey, CitationMatch, CitationMatchStatus};
+pub use database_info::{DatabaseInfo, LinkInfo, SearchField};
+pub use document_summary::DocumentSummary;
 pub use elocation_id::ELocationID;
 pub use grant::{Grant, GrantList};
+pub use history::HistoryHandle;
+pub use id_conversion::{ConverterIdType, IdRecord, IdVersion};
 pub use identifier::Identifier;
 pub use journal::Journal;
 pub use journal_issue::JournalIssue;
 pub use keyword::{Keyword, KeywordList};
+pub use link::{Link, LinkName, LinkSet, LinkSetDb};
 pub use medline_citation::MedlineCitation;
 pub use medline_journal_info::MedlineJournalInfo;
 pub use mesh::{MeshHeading, MeshTermPart};
 pub use other_id::OtherID;
-pub use pagination::Pagination;
+pub use pagination::{PageRange, Pagination};
 pub use publication_type::PublicationType;
 pub use pubmed_article::PubmedArticle;
 pub use pubmed_data::PubmedData;
-pub use pubmed_date::PubMedDate;
+pub use pubmed_date::{DateRange, PubMedDate};
 pub use reference::Reference;
+pub use rich_text::{RichText, TextFormat, TextSpan};
+pub use search_result::SearchResult;
+pub use spelling::{SpelledQueryPart, SpellingSuggestion, TermReplacement};
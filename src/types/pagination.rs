@@ -1,6 +1,211 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub enum Pagination {
-    MedlinePgn(String),
+use crate::helpers::missing_tag_warning;
+
+/// One page or page range, with abbreviated end pages expanded, e.g.
+/// `1123-9` becomes 1123 to 1129.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PageRange {
+    pub start: String,
+    pub end: Option<String>,
+}
+
+impl PageRange {
+    /// Parses a single MEDLINE page or range like `123`, `1123-9`,
+    /// `S12-4` or `e0123456`. `None` if `s` does not look like pages.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().trim_end_matches('.');
+        let (start, end) = match s.split_once(['-', '–']) {
+            Some((start, end)) => (start.trim(), Some(end.trim())),
+            None => (s, None),
+        };
+        if !Self::is_page(start) || !end.is_none_or(Self::is_page) {
+            return None;
+        }
+        Some(Self {
+            start: start.to_string(),
+            end: end
+                .map(|end| Self::expand(start, end))
+                .filter(|end| end != start),
+        })
+    }
+
+    /// Whether this is an electronic article number like `e0123456` rather
+    /// than a printed page.
+    #[must_use]
+    pub fn is_article_number(&self) -> bool {
+        if self.end.is_some() {
+            return false;
+        }
+        let (prefix, digits, suffix) = Self::split(&self.start);
+        suffix.is_empty()
+            && ((prefix.eq_ignore_ascii_case("e") && !digits.is_empty()) || digits.len() >= 6)
+    }
+
+    /// The number of pages, if both ends are numeric. Article numbers have
+    /// no page count.
+    #[must_use]
+    pub fn page_count(&self) -> Option<u32> {
+        if self.is_article_number() {
+            return None;
+        }
+        let start: u32 = Self::split(&self.start).1.parse().ok()?;
+        let end: u32 = match &self.end {
+            Some(end) => Self::split(end).1.parse().ok()?,
+            None => start,
+        };
+        end.checked_sub(start).map(|n| n + 1)
+    }
+
+    fn is_page(s: &str) -> bool {
+        !s.is_empty() && s.chars().all(char::is_alphanumeric)
+    }
+
+    // Splits "S123a" into ("S", "123", "a")
+    fn split(s: &str) -> (&str, &str, &str) {
+        let digits_start = s.find(|c: char| c.is_ascii_digit()).unwrap_or(s.len());
+        let digits_end = s[digits_start..]
+            .find(|c: char| !c.is_ascii_digit())
+            .map_or(s.len(), |i| digits_start + i);
+        (
+            &s[..digits_start],
+            &s[digits_start..digits_end],
+            &s[digits_end..],
+        )
+    }
+
+    // Completes an abbreviated end page from the start page: "1123-9" ends
+    // at 1129, "S123-9" at S129
+    fn expand(start: &str, end: &str) -> String {
+        let (start_prefix, start_digits, _) = Self::split(start);
+        let (end_prefix, end_digits, end_suffix) = Self::split(end);
+        if end_digits.is_empty() || end_digits.len() >= start_digits.len() {
+            return match end_prefix {
+                "" if !end_digits.is_empty() => format!("{start_prefix}{end}"),
+                _ => end.to_string(),
+            };
+        }
+        let prefix = if end_prefix.is_empty() {
+            start_prefix
+        } else {
+            end_prefix
+        };
+        format!(
+            "{prefix}{}{end_digits}{end_suffix}",
+            &start_digits[..start_digits.len() - end_digits.len()]
+        )
+    }
+}
+
+impl fmt::Display for PageRange {
+    /// Formats the range for citations, with an en dash, e.g. `1123–1129`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.end {
+            Some(end) => write!(f, "{}–{end}", self.start),
+            None => write!(f, "{}", self.start),
+        }
+    }
+}
+
+/// The `Pagination` of an article. `medline_pgn` holds the MEDLINE page
+/// string, which may abbreviate the end page and list several ranges, e.g.
+/// `321-9, 334-6`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pagination {
+    pub start_page: Option<String>,
+    pub end_page: Option<String>,
+    pub medline_pgn: Option<String>,
+}
+
+impl Pagination {
+    #[must_use]
+    pub fn new_from_xml(node: &roxmltree::Node) -> Self {
+        let mut ret = Self::default();
+        for n in node.children().filter(roxmltree::Node::is_element) {
+            let text = n
+                .text()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty());
+            match n.tag_name().name() {
+                "StartPage" => ret.start_page = text,
+                "EndPage" => ret.end_page = text,
+                "MedlinePgn" => ret.medline_pgn = text,
+                x => missing_tag_warning(&format!("Not covered in Pagination: '{x}'")),
+            }
+        }
+        ret
+    }
+
+    /// The page ranges, from `MedlinePgn` if present, otherwise from
+    /// `StartPage` and `EndPage`. Parts that are not pages, like the
+    /// "discussion" in `S12-4; discussion S15`, are skipped.
+    #[must_use]
+    pub fn ranges(&self) -> Vec<PageRange> {
+        if let Some(pgn) = &self.medline_pgn {
+            return pgn
+                .split([',', ';'])
+                .filter_map(|part| {
+                    PageRange::parse(part).or_else(|| {
+                        part.split_whitespace()
+                            .next_back()
+                            .and_then(PageRange::parse)
+                    })
+                })
+                .collect();
+        }
+        let Some(start) = &self.start_page else {
+            return vec![];
+        };
+        let range = match &self.end_page {
+            Some(end) => PageRange::parse(&format!("{start}-{end}")),
+            None => PageRange::parse(start),
+        };
+        range.into_iter().collect()
+    }
+
+    /// The first page, e.g. `1123` for `1123-9`.
+    #[must_use]
+    pub fn first_page(&self) -> Option<String> {
+        self.start_page
+            .clone()
+            .or_else(|| Some(self.ranges().first()?.start.clone()))
+    }
+
+    /// The last page, expanded, e.g. `1129` for `1123-9`.
+    #[must_use]
+    pub fn last_page(&self) -> Option<String> {
+        if self.end_page.is_some() {
+            return self.end_page.clone();
+        }
+        let last = self.ranges().pop()?;
+        Some(last.end.unwrap_or(last.start))
+    }
+
+    /// The total number of pages over all ranges; `None` if any range has
+    /// no page count, e.g. for article numbers.
+    #[must_use]
+    pub fn page_count(&self) -> Option<u32> {
+        let ranges = self.ranges();
+        if ranges.is_empty() {
+            return None;
+        }
+        ranges.iter().map(PageRange::page_count).sum()
+    }
+
+    /// Whether the article is identified by an article number like
+    /// `e0123456` instead of printed pages.
+    #[must_use]
+    pub fn is_article_number(&self) -> bool {
+        matches!(self.ranges().as_slice(), [range] if range.is_article_number())
+    }
+}
+
+impl fmt::Display for Pagination {
+    /// Formats all ranges for citations, e.g. `321–329, 334–336`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ranges: Vec<String> = self.ranges().iter().map(ToString::to_string).collect();
+        write!(f, "{}", ranges.join(", "))
+    }
 }
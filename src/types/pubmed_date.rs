@@ -11,6 +11,15 @@ pub struct PubMedDate {
     pub minute: i8,
     pub date_type: Option<String>,
     pub pub_status: Option<String>,
+    /// The free-text `MedlineDate`, e.g. "1998 Dec-1999 Jan"
+    #[serde(default)]
+    pub medline_date: Option<String>,
+    /// The `Season`, e.g. "Spring"
+    #[serde(default)]
+    pub season: Option<String>,
+    /// The period covered by `medline_date` or `season`
+    #[serde(default)]
+    pub range: Option<DateRange>,
 }
 
 impl PubMedDate {
@@ -27,6 +36,9 @@ impl PubMedDate {
             pub_status: node
                 .attribute("PubStatus")
                 .map(std::string::ToString::to_string),
+            medline_date: None,
+            season: None,
+            range: None,
         };
 
         for n in node.children().filter(roxmltree::Node::is_element) {
@@ -36,13 +48,55 @@ impl PubMedDate {
                 "Day" => ret.day = n.text().map_or(0, |v| v.parse::<u8>().unwrap_or(0)),
                 "Hour" => ret.hour = n.text().map_or(-1, |v| v.parse::<i8>().unwrap_or(-1)),
                 "Minute" => ret.minute = n.text().map_or(-1, |v| v.parse::<i8>().unwrap_or(-1)),
-                "MedlineDate" | "Season" => {
-                    // TODO
-                    // Example: https://eutils.ncbi.nlm.nih.gov/entrez/eutils/efetch.fcgi?db=pubmed&retmode=xml&id=11364263
-                }
+                "MedlineDate" => ret.medline_date = n.text().map(|v| v.trim().to_string()),
+                "Season" => ret.season = n.text().map(|v| v.trim().to_string()),
                 x => missing_tag_warning(&format!("Not covered in PubMedDate: '{x}'")),
             }
         }
+        ret.range = match (&ret.medline_date, &ret.season) {
+            (Some(medline_date), _) => DateRange::parse(medline_date),
+            (None, Some(season)) if ret.year != 0 => {
+                DateRange::parse(&format!("{} {season}", ret.year))
+            }
+            _ => None,
+        };
+        // A MedlineDate replaces Year/Month/Day, so fill them from the start
+        if let (0, Some(range)) = (ret.year, &ret.range) {
+            ret.year = range.start_year;
+            ret.month = range.start_month;
+            ret.day = range.start_day;
+        }
+        match ret.precision() {
+            0 => None,
+            _ => Some(ret),
+        }
+    }
+
+    /// Parses ESummary/EInfo style dates, e.g. "2012/06/14 00:00".
+    pub(crate) fn new_from_esummary_str(s: &str, pub_status: Option<String>) -> Option<Self> {
+        let (date, time) = s.trim().split_once(' ').unwrap_or((s.trim(), ""));
+        let mut date_parts = date.split('/');
+        let mut time_parts = time.split(':');
+        let ret = Self {
+            year: date_parts.next()?.parse::<u32>().ok()?,
+            month: date_parts
+                .next()
+                .map_or(0, |v| v.parse::<u8>().unwrap_or(0)),
+            day: date_parts
+                .next()
+                .map_or(0, |v| v.parse::<u8>().unwrap_or(0)),
+            hour: time_parts
+                .next()
+                .map_or(-1, |v| v.parse::<i8>().unwrap_or(-1)),
+            minute: time_parts
+                .next()
+                .map_or(-1, |v| v.parse::<i8>().unwrap_or(-1)),
+            date_type: None,
+            pub_status,
+            medline_date: None,
+            season: None,
+            range: None,
+        };
         match ret.precision() {
             0 => None,
             _ => Some(ret),
@@ -51,25 +105,35 @@ impl PubMedDate {
 
     fn parse_month_from_xml(node: &roxmltree::Node) -> u8 {
         match node.text() {
-            Some(t) => match t.to_lowercase().as_str() {
-                "jan" => 1,
-                "feb" => 2,
-                "mar" => 3,
-                "apr" => 4,
-                "may" => 5,
-                "jun" => 6,
-                "jul" => 7,
-                "aug" => 8,
-                "sep" => 9,
-                "oct" => 10,
-                "nov" => 11,
-                "dec" => 12,
-                other => other.parse::<u8>().unwrap_or(0),
-            },
+            Some(t) => Self::month_from_name(t).unwrap_or_else(|| t.parse::<u8>().unwrap_or(0)),
             None => 0,
         }
     }
 
+    // "Jan", "January" or "Sept"
+    fn month_from_name(name: &str) -> Option<u8> {
+        const MONTHS: [&str; 12] = [
+            "january",
+            "february",
+            "march",
+            "april",
+            "may",
+            "june",
+            "july",
+            "august",
+            "september",
+            "october",
+            "november",
+            "december",
+        ];
+        let name = name.to_lowercase();
+        if name.len() < 3 {
+            return None;
+        }
+        let month = MONTHS.iter().position(|m| m.starts_with(&name))?;
+        u8::try_from(month + 1).ok()
+    }
+
     // 13=minute, 12=hour, 11=day, 10=month, 9=year; same as Wikidata/wikibase
     #[must_use]
     pub fn precision(&self) -> u8 {
@@ -88,3 +152,100 @@ impl PubMedDate {
         }
     }
 }
+
+/// A period parsed from a `MedlineDate` or `Season`. Parts finer than
+/// `precision` are 0, as in [`PubMedDate`]; both ends are inclusive.
+///
+/// Seasons are taken as Spring = Mar-May, Summer = Jun-Aug, Fall/Autumn =
+/// Sep-Nov and Winter = Dec-Feb, so "2001 Winter" runs from December 2001
+/// to February 2002.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DateRange {
+    pub start_year: u32,
+    pub start_month: u8,
+    pub start_day: u8,
+    pub end_year: u32,
+    pub end_month: u8,
+    pub end_day: u8,
+    /// 9=year, 10=month, 11=day, as in [`PubMedDate::precision`]. For ends
+    /// of different precision, e.g. "1998 Dec 15-1999 Jan", this is the
+    /// coarser one; the finer end keeps its day.
+    pub precision: u8,
+}
+
+impl DateRange {
+    /// Parses MEDLINE free-text dates like "1998 Dec-1999 Jan",
+    /// "2000 Spring-Summer", "1999 Jun 29-Jul 5" or "1977-1978". Words that
+    /// are not years, months, days or seasons are ignored.
+    #[must_use]
+    pub fn parse(s: &str) -> Option<Self> {
+        let mut years: Vec<u32> = vec![];
+        // (month, day) in order; a day belongs to the month before it
+        let mut months: Vec<(u8, u8)> = vec![];
+        let mut seasons: Vec<(u8, u8)> = vec![];
+        for word in s
+            .split(|c: char| c.is_whitespace() || c == '-' || c == ',' || c == '/')
+            .map(|w| w.trim_end_matches('.'))
+            .filter(|w| !w.is_empty())
+        {
+            if let Ok(number) = word.parse::<u32>() {
+                match (word.len(), u8::try_from(number), months.last_mut()) {
+                    (4, _, _) => years.push(number),
+                    (1 | 2, Ok(day @ 1..=31), Some(last)) => {
+                        // "Dec 23-30" ends on Dec 30
+                        if last.1 == 0 {
+                            last.1 = day;
+                        } else {
+                            let month = last.0;
+                            months.push((month, day));
+                        }
+                    }
+                    _ => {}
+                }
+            } else if let Some(season) = Self::season_months(word) {
+                seasons.push(season);
+            } else if let Some(month) = PubMedDate::month_from_name(word) {
+                months.push((month, 0));
+            }
+        }
+
+        let mut ret = Self {
+            start_year: *years.first()?,
+            start_month: 0,
+            start_day: 0,
+            end_year: *years.last()?,
+            end_month: 0,
+            end_day: 0,
+            precision: 9,
+        };
+        if let (Some(first), Some(last)) = (months.first(), months.last()) {
+            (ret.start_month, ret.start_day) = *first;
+            (ret.end_month, ret.end_day) = *last;
+            ret.precision = if ret.start_day == 0 || ret.end_day == 0 {
+                10
+            } else {
+                11
+            };
+        } else if let (Some(first), Some(last)) = (seasons.first(), seasons.last()) {
+            ret.start_month = first.0;
+            ret.end_month = last.1;
+            ret.precision = 10;
+        }
+        // "1998 Dec-Jan" or "2001 Winter" end in the following year
+        if years.len() == 1 && ret.end_month < ret.start_month {
+            ret.end_year += 1;
+        }
+        Some(ret)
+    }
+
+    // First and last month of a season
+    fn season_months(name: &str) -> Option<(u8, u8)> {
+        match name.to_lowercase().as_str() {
+            "spring" => Some((3, 5)),
+            "summer" => Some((6, 8)),
+            "fall" | "autumn" => Some((9, 11)),
+            "winter" => Some((12, 2)),
+            _ => None,
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use serde::{Deserialize, Serialize};
 
 use crate::helpers::missing_tag_warning;
-use crate::types::article_id::ArticleIdList;
+use crate::types::article_id::{ArticleIdList, ArticleIdType};
+use crate::types::rich_text::RichText;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Reference {
-    pub citation: Option<String>,
+    pub citation: Option<RichText>,
     pub article_ids: Option<ArticleIdList>,
 }
 
@@ -17,11 +18,38 @@ impl Reference {
         };
         for n in node.children().filter(roxmltree::Node::is_element) {
             match n.tag_name().name() {
-                "Citation" => ret.citation = n.text().map(std::string::ToString::to_string),
+                "Citation" => ret.citation = Some(RichText::new_from_xml(&n)),
                 "ArticleIdList" => ret.article_ids = Some(ArticleIdList::new_from_xml(&n)),
                 x => missing_tag_warning(&format!("Not covered in Reference: '{x}'")),
             }
         }
         ret
     }
+
+    /// Returns the first normalized ID of the given type, if the reference
+    /// has an `ArticleIdList`.
+    #[must_use]
+    pub fn article_id(&self, id_type: &ArticleIdType) -> Option<String> {
+        self.article_ids.as_ref()?.get(id_type)
+    }
+
+    #[must_use]
+    pub fn pmid(&self) -> Option<u64> {
+        self.article_ids.as_ref()?.pmid()
+    }
+
+    #[must_use]
+    pub fn doi(&self) -> Option<String> {
+        self.article_id(&ArticleIdType::Doi)
+    }
+
+    #[must_use]
+    pub fn pmcid(&self) -> Option<String> {
+        self.article_id(&ArticleIdType::Pmc)
+    }
+
+    #[must_use]
+    pub fn pii(&self) -> Option<String> {
+        self.article_id(&ArticleIdType::Pii)
+    }
 }
@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Inline formatting found in PubMed titles and abstracts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TextFormat {
+    Italic,
+    Bold,
+    Underline,
+    Superscript,
+    Subscript,
+    SmallCaps,
+    /// Text content of a MathML expression
+    Math,
+}
+
+impl TextFormat {
+    fn from_tag(tag: &str) -> Option<Self> {
+        match tag {
+            "i" => Some(Self::Italic),
+            "b" => Some(Self::Bold),
+            "u" => Some(Self::Underline),
+            "sup" => Some(Self::Superscript),
+            "sub" => Some(Self::Subscript),
+            "sc" => Some(Self::SmallCaps),
+            "math" => Some(Self::Math),
+            _ => None,
+        }
+    }
+
+    fn html_tags(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Italic => ("<i>", "</i>"),
+            Self::Bold => ("<b>", "</b>"),
+            Self::Underline => ("<u>", "</u>"),
+            Self::Superscript => ("<sup>", "</sup>"),
+            Self::Subscript => ("<sub>", "</sub>"),
+            Self::SmallCaps => ("<span style=\"font-variant:small-caps\">", "</span>"),
+            Self::Math => ("<span class=\"math\">", "</span>"),
+        }
+    }
+
+    // Formats without a Markdown equivalent are left unmarked
+    fn markdown_marks(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Italic => ("*", "*"),
+            Self::Bold => ("**", "**"),
+            Self::Superscript => ("<sup>", "</sup>"),
+            Self::Subscript => ("<sub>", "</sub>"),
+            Self::Math => ("`", "`"),
+            Self::Underline | Self::SmallCaps => ("", ""),
+        }
+    }
+}
+
+/// A run of text with the same formatting. `formats` lists the enclosing
+/// formats from outermost to innermost.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextSpan {
+    pub text: String,
+    pub formats: Vec<TextFormat>,
+}
+
+/// Mixed-content text such as `ArticleTitle`, keeping inline markup like
+/// `<i>`, `<sup>` or MathML. `Display` renders the plain text.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RichText {
+    pub spans: Vec<TextSpan>,
+}
+
+impl RichText {
+    #[must_use]
+    pub fn new_from_xml(node: &roxmltree::Node) -> Self {
+        let mut ret = Self::default();
+        ret.add_children(node, &mut vec![]);
+        ret
+    }
+
+    fn add_children(&mut self, node: &roxmltree::Node, formats: &mut Vec<TextFormat>) {
+        for n in node.children() {
+            if n.is_text() {
+                self.push(n.text().unwrap_or(""), formats);
+            } else if n.is_element() {
+                match TextFormat::from_tag(n.tag_name().name()) {
+                    Some(format) => {
+                        formats.push(format);
+                        self.add_children(&n, formats);
+                        formats.pop();
+                    }
+                    None => self.add_children(&n, formats), // Keep the text of unknown tags
+                }
+            }
+        }
+    }
+
+    fn push(&mut self, text: &str, formats: &[TextFormat]) {
+        if text.is_empty() {
+            return;
+        }
+        match self.spans.last_mut() {
+            Some(last) if last.formats == formats => last.text.push_str(text),
+            _ => self.spans.push(TextSpan {
+                text: text.to_string(),
+                formats: formats.to_vec(),
+            }),
+        }
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.spans.iter().all(|s| s.text.is_empty())
+    }
+
+    /// Removes leading and trailing whitespace, dropping spans left empty.
+    #[must_use]
+    pub fn trim(mut self) -> Self {
+        while let Some(first) = self.spans.first_mut() {
+            first.text = first.text.trim_start().to_string();
+            if !first.text.is_empty() {
+                break;
+            }
+            self.spans.remove(0);
+        }
+        while let Some(last) = self.spans.last_mut() {
+            last.text.truncate(last.text.trim_end().len());
+            if !last.text.is_empty() {
+                break;
+            }
+            self.spans.pop();
+        }
+        self
+    }
+
+    /// All text, without markup.
+    #[must_use]
+    pub fn plain_text(&self) -> String {
+        self.spans.iter().map(|s| s.text.as_str()).collect()
+    }
+
+    /// HTML with the text escaped and formatting as inline tags.
+    #[must_use]
+    pub fn to_html(&self) -> String {
+        self.render(TextFormat::html_tags, Self::escape_html)
+    }
+
+    /// Markdown with `*italic*`, `**bold**` and `` `math` ``; super- and
+    /// subscripts use HTML tags, which most Markdown renderers accept.
+    #[must_use]
+    pub fn to_markdown(&self) -> String {
+        self.render(TextFormat::markdown_marks, Self::escape_markdown)
+    }
+
+    // Opens and closes marks as the format stack changes between spans
+    fn render(
+        &self,
+        marks: fn(TextFormat) -> (&'static str, &'static str),
+        escape: fn(&str) -> String,
+    ) -> String {
+        let mut ret = String::new();
+        let mut open: Vec<TextFormat> = vec![];
+        for span in &self.spans {
+            let common = open
+                .iter()
+                .zip(&span.formats)
+                .take_while(|(a, b)| a == b)
+                .count();
+            while open.len() > common {
+                if let Some(format) = open.pop() {
+                    ret.push_str(marks(format).1);
+                }
+            }
+            for format in &span.formats[common..] {
+                ret.push_str(marks(*format).0);
+                open.push(*format);
+            }
+            ret.push_str(&escape(&span.text));
+        }
+        while let Some(format) = open.pop() {
+            ret.push_str(marks(format).1);
+        }
+        ret
+    }
+
+    fn escape_html(text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    }
+
+    fn escape_markdown(text: &str) -> String {
+        let mut ret = String::with_capacity(text.len());
+        for c in text.chars() {
+            if matches!(c, '\\' | '*' | '_' | '`' | '[' | ']' | '<' | '>') {
+                ret.push('\\');
+            }
+            ret.push(c);
+        }
+        ret
+    }
+}
+
+impl fmt::Display for RichText {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for span in &self.spans {
+            write!(f, "{}", span.text)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&str> for RichText {
+    fn from(text: &str) -> Self {
+        let mut ret = Self::default();
+        ret.push(text, &[]);
+        ret
+    }
+}
@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::json_u64;
+
+/// One page of an ESearch result, including the total hit count so callers
+/// can tell whether the ID list was truncated.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SearchResult {
+    pub count: u64,
+    pub ret_max: u64,
+    pub ret_start: u64,
+    pub ids: Vec<u64>,
+    pub query_translation: Option<String>,
+    pub warnings: Vec<String>,
+    pub errors: Vec<String>,
+    /// Set if the results are for a spelling-corrected query, see
+    /// [`crate::Client::search_with_spelling_fallback`].
+    pub corrected_query: Option<String>,
+}
+
+impl SearchResult {
+    /// Parses the `esearchresult` object of an ESearch JSON response.
+    /// Returns `None` if the object is missing or reports an `ERROR`.
+    pub(crate) fn new_from_json(json: &serde_json::Value) -> Option<Self> {
+        let result = json.get("esearchresult")?;
+        if result.get("ERROR").is_some() {
+            return None;
+        }
+        let ids = result["idlist"]
+            .as_array()?
+            .iter()
+            .filter_map(|id| {
+                id.as_str().and_then(|x| {
+                    if let Ok(u) = x.parse::<u64>() {
+                        Some(u)
+                    } else {
+                        eprintln!("PubMed::SearchResult: '{x}' should be a numeric ID");
+                        None
+                    }
+                })
+            })
+            .collect();
+        Some(Self {
+            count: json_u64(&result["count"]).unwrap_or(0),
+            ret_max: json_u64(&result["retmax"]).unwrap_or(0),
+            ret_start: json_u64(&result["retstart"]).unwrap_or(0),
+            ids,
+            query_translation: result["querytranslation"]
+                .as_str()
+                .map(std::string::ToString::to_string),
+            warnings: Self::json_messages(&result["warninglist"]),
+            errors: Self::json_messages(&result["errorlist"]),
+            corrected_query: None,
+        })
+    }
+
+    /// Returns `true` if there are more hits beyond this page.
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.ret_start + (self.ids.len() as u64) < self.count
+    }
+
+    // Flattens `warninglist`/`errorlist` into "category: message" strings
+    fn json_messages(value: &serde_json::Value) -> Vec<String> {
+        let Some(map) = value.as_object() else {
+            return vec![];
+        };
+        map.iter()
+            .filter_map(|(category, messages)| Some((category, messages.as_array()?)))
+            .flat_map(|(category, messages)| {
+                messages
+                    .iter()
+                    .filter_map(serde_json::Value::as_str)
+                    .filter(|m| !m.is_empty())
+                    .map(move |m| format!("{category}: {m}"))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+
+use crate::helpers::missing_tag_warning;
+
+/// A segment of an ESpell `SpelledQuery`: either kept as typed, or replaced
+/// by a spelling correction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SpelledQueryPart {
+    Original(String),
+    Replaced(String),
+}
+
+/// A single corrected term; `original` is `None` if the replaced term could
+/// not be lined up with the submitted query.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TermReplacement {
+    pub original: Option<String>,
+    pub replacement: String,
+}
+
+/// The ESpell suggestion for a query.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SpellingSuggestion {
+    pub query: String,
+    pub corrected_query: Option<String>,
+    pub spelled_query: Vec<SpelledQueryPart>,
+}
+
+impl SpellingSuggestion {
+    pub(crate) fn new_from_xml(node: &roxmltree::Node) -> Self {
+        let mut ret = Self {
+            query: String::new(),
+            corrected_query: None,
+            spelled_query: vec![],
+        };
+        for n in node.children().filter(roxmltree::Node::is_element) {
+            match n.tag_name().name() {
+                "Query" => ret.query = n.text().unwrap_or("").to_string(),
+                "CorrectedQuery" => {
+                    ret.corrected_query = n
+                        .text()
+                        .map(str::trim)
+                        .filter(|q| !q.is_empty())
+                        .map(std::string::ToString::to_string);
+                }
+                "SpelledQuery" => {
+                    for part in n.children().filter(roxmltree::Node::is_element) {
+                        let text = part.text().unwrap_or("").to_string();
+                        match part.tag_name().name() {
+                            "Original" => ret.spelled_query.push(SpelledQueryPart::Original(text)),
+                            "Replaced" => ret.spelled_query.push(SpelledQueryPart::Replaced(text)),
+                            x => missing_tag_warning(&format!(
+                                "Not covered in SpellingSuggestion::SpelledQuery: '{x}'"
+                            )),
+                        }
+                    }
+                }
+                "Database" | "ERROR" => {}
+                x => missing_tag_warning(&format!("Not covered in SpellingSuggestion: '{x}'")),
+            }
+        }
+        ret
+    }
+
+    /// Returns `true` if ESpell suggested a query different from the input.
+    #[must_use]
+    pub fn has_correction(&self) -> bool {
+        self.corrected_query
+            .as_ref()
+            .is_some_and(|q| !q.eq_ignore_ascii_case(self.query.trim()))
+    }
+
+    /// The replaced terms, each paired with the query term it replaces. The
+    /// pairing relies on ESpell returning one segment per whitespace-separated
+    /// query term; if that does not hold, `original` is left empty.
+    #[must_use]
+    pub fn replacements(&self) -> Vec<TermReplacement> {
+        let terms: Vec<&str> = self.query.split_whitespace().collect();
+        let parts: Vec<&SpelledQueryPart> = self
+            .spelled_query
+            .iter()
+            .filter(|p| match p {
+                SpelledQueryPart::Original(s) | SpelledQueryPart::Replaced(s) => {
+                    !s.trim().is_empty()
+                }
+            })
+            .collect();
+        let aligned = parts.len() == terms.len();
+        parts
+            .iter()
+            .enumerate()
+            .filter_map(|(i, part)| match part {
+                SpelledQueryPart::Replaced(replacement) => Some(TermReplacement {
+                    original: aligned.then(|| terms[i].to_string()),
+                    replacement: replacement.to_string(),
+                }),
+                SpelledQueryPart::Original(_) => None,
+            })
+            .collect()
+    }
+}